@@ -0,0 +1,238 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+use crate::models::*;
+use crate::parser::{parse_hex_string, ParserState};
+use crate::serial::{dispatch_data, SerialManager};
+use crate::storage::LogState;
+use super::{apply_profile, read_command_groups, read_profiles};
+
+/// 自动连接执行结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoConnectEvent {
+    /// 规则 ID
+    pub rule_id: String,
+    /// 规则名称
+    pub rule_name: String,
+    /// 串口名称
+    pub port_name: String,
+    /// 错误信息
+    pub error: Option<String>,
+    /// 已打开，但激活协议未能保存到数据库时的警告
+    pub warning: Option<String>,
+}
+
+/// 获取自动连接规则存储路径
+fn get_rules_path(app_handle: &AppHandle<impl Runtime>) -> PathBuf {
+    let app_data_dir = app_handle.path().app_data_dir()
+        .expect("无法获取应用数据目录");
+    
+    fs::create_dir_all(&app_data_dir).ok();
+    
+    app_data_dir.join("autoconnect.json")
+}
+
+/// 读取所有自动连接规则
+fn read_rules(app_handle: &AppHandle<impl Runtime>) -> Result<Vec<AutoConnectRule>, String> {
+    let rules_path = get_rules_path(app_handle);
+    
+    if !rules_path.exists() {
+        return Ok(Vec::new());
+    }
+    
+    let content = fs::read_to_string(&rules_path)
+        .map_err(|e| format!("读取自动连接规则失败: {}", e))?;
+    
+    serde_json::from_str(&content)
+        .map_err(|e| format!("解析自动连接规则失败: {}", e))
+}
+
+/// 写入所有自动连接规则
+fn write_rules(app_handle: &AppHandle<impl Runtime>, rules: &[AutoConnectRule]) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(rules)
+        .map_err(|e| format!("序列化自动连接规则失败: {}", e))?;
+    
+    fs::write(get_rules_path(app_handle), content)
+        .map_err(|e| format!("写入自动连接规则失败: {}", e))
+}
+
+/// 获取所有自动连接规则
+#[tauri::command]
+pub fn list_autoconnect_rules<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<Vec<AutoConnectRule>, String> {
+    read_rules(&app_handle)
+}
+
+/// 保存自动连接规则
+#[tauri::command]
+pub fn save_autoconnect_rule<R: Runtime>(
+    app_handle: AppHandle<R>,
+    rule: AutoConnectRule,
+) -> Result<(), String> {
+    let mut rules = read_rules(&app_handle)?;
+    
+    if let Some(existing) = rules.iter_mut().find(|r| r.id == rule.id) {
+        *existing = rule;
+    } else {
+        rules.push(rule);
+    }
+    
+    write_rules(&app_handle, &rules)
+}
+
+/// 删除自动连接规则
+#[tauri::command]
+pub fn delete_autoconnect_rule<R: Runtime>(
+    app_handle: AppHandle<R>,
+    id: String,
+) -> Result<(), String> {
+    let mut rules = read_rules(&app_handle)?;
+    
+    rules.retain(|r| r.id != id);
+    
+    write_rules(&app_handle, &rules)
+}
+
+/// 处理串口变化：通知前端并按规则自动连接新出现的设备
+pub(crate) fn handle_port_change<R: Runtime>(app_handle: &AppHandle<R>, change: &PortChange) {
+    let _ = app_handle.emit("serial:ports-changed", change);
+    
+    let manager = app_handle.state::<SerialManager>();
+    
+    // 当前会话的设备被拔出时关闭会话，以便接入下一块设备
+    let session_port_removed = manager.config()
+        .map(|c| c.transport == TransportType::Serial && change.removed.contains(&c.port_name))
+        .unwrap_or(false);
+    if session_port_removed && manager.close().is_ok() {
+        let _ = app_handle.emit("serial:status-changed", &SerialStatus::Closed);
+    }
+    
+    // 同一时间只有一个会话，已连接时不再自动连接
+    if manager.status() == SerialStatus::Open || change.arrived.is_empty() {
+        return;
+    }
+    
+    let rules = match read_rules(app_handle) {
+        Ok(rules) => rules,
+        Err(e) => {
+            eprintln!("Warning: Failed to load auto-connect rules: {}", e);
+            return;
+        }
+    };
+    
+    let profiles = match read_profiles(app_handle) {
+        Ok(profiles) => profiles,
+        Err(e) => {
+            eprintln!("Warning: Failed to load connection profiles: {}", e);
+            return;
+        }
+    };
+    
+    for port in &change.arrived {
+        let Some(rule) = rules.iter().find(|r| r.enabled && r.selector.matches(port) && opens_serial_port(r, &profiles)) else {
+            continue;
+        };
+        
+        let (warning, error) = match run_rule(app_handle, rule, &port.name) {
+            Ok(warning) => (warning, None),
+            Err(e) => (None, Some(e)),
+        };
+        let opened = error.is_none();
+        
+        let _ = app_handle.emit("autoconnect:triggered", &AutoConnectEvent {
+            rule_id: rule.id.clone(),
+            rule_name: rule.name.clone(),
+            port_name: port.name.clone(),
+            error,
+            warning,
+        });
+        
+        if opened {
+            break;
+        }
+    }
+}
+
+/// 规则是否由串口接入触发
+///
+/// 只有档案传输类型为物理串口的规则才使用新出现的串口；回环、伪终端与 WebSocket 档案与串口无关，
+/// 接入设备时不打开。档案不存在时仍触发，由执行时报告错误。
+fn opens_serial_port(rule: &AutoConnectRule, profiles: &[ConnectionProfile]) -> bool {
+    profiles.iter()
+        .find(|p| p.name == rule.profile)
+        .is_none_or(|p| p.config.transport == TransportType::Serial)
+}
+
+/// 执行自动连接规则，返回激活协议未能保存到数据库时的警告
+fn run_rule<R: Runtime>(app_handle: &AppHandle<R>, rule: &AutoConnectRule, port_name: &str) -> Result<Option<String>, String> {
+    let profile = read_profiles(app_handle)?
+        .into_iter()
+        .find(|p| p.name == rule.profile)
+        .ok_or_else(|| format!("未找到连接配置: {}", rule.profile))?;
+    
+    let manager = app_handle.state::<SerialManager>();
+    let parser_state = app_handle.state::<ParserState>();
+    let warning = apply_profile(app_handle, &manager, &parser_state, &profile, Some(port_name.to_string()))?;
+    
+    if rule.start_logging {
+        start_default_logging(app_handle)?;
+    }
+    
+    if let Some(group_id) = &rule.command_group {
+        let group = read_command_groups(app_handle)?
+            .into_iter()
+            .find(|g| &g.id == group_id)
+            .ok_or_else(|| format!("未找到命令组: {}", group_id))?;
+        
+        spawn_command_sequence(app_handle.clone(), group, rule.interval_ms);
+    }
+    
+    Ok(warning)
+}
+
+/// 按日志配置开始记录，未配置目录时使用应用数据目录
+fn start_default_logging<R: Runtime>(app_handle: &AppHandle<R>) -> Result<(), String> {
+    let log_state = app_handle.state::<LogState>();
+    let mut manager = log_state.manager.lock()
+        .map_err(|e| format!("Failed to lock log manager: {}", e))?;
+    
+    let dir = if manager.get_config().log_dir.is_empty() {
+        app_handle.path().app_data_dir().ok()
+            .map(|d| d.to_string_lossy().to_string())
+    } else {
+        None
+    };
+    
+    manager.start_logging(dir.as_deref())
+}
+
+/// 在后台依次发送命令组中的命令
+fn spawn_command_sequence<R: Runtime>(app_handle: AppHandle<R>, group: CommandGroup, interval_ms: u64) {
+    thread::spawn(move || {
+        let manager = app_handle.state::<SerialManager>();
+        
+        for (index, command) in group.commands.iter().enumerate() {
+            if index > 0 {
+                thread::sleep(Duration::from_millis(interval_ms));
+            }
+            
+            let result = parse_hex_string(&command.data)
+                .and_then(|bytes| manager.send(&bytes).map(|_| bytes));
+            
+            match result {
+                Ok(bytes) => {
+                    dispatch_data(&app_handle, &bytes, DataDirection::Tx, DataFormat::Hex);
+                }
+                Err(e) => {
+                    let _ = app_handle.emit("autoconnect:error", format!("{}: {}", command.name, e));
+                    break;
+                }
+            }
+        }
+    });
+}
@@ -61,12 +61,9 @@ fn get_commands_path(app_handle: &AppHandle<impl Runtime>) -> PathBuf {
     app_data_dir.join("commands.json")
 }
 
-/// 获取所有命令组
-#[tauri::command]
-pub fn list_command_groups<R: Runtime>(
-    app_handle: AppHandle<R>,
-) -> Result<Vec<CommandGroup>, String> {
-    let commands_path = get_commands_path(&app_handle);
+/// 读取所有命令组
+pub(crate) fn read_command_groups(app_handle: &AppHandle<impl Runtime>) -> Result<Vec<CommandGroup>, String> {
+    let commands_path = get_commands_path(app_handle);
     
     if !commands_path.exists() {
         return Ok(Vec::new());
@@ -75,10 +72,16 @@ pub fn list_command_groups<R: Runtime>(
     let content = fs::read_to_string(&commands_path)
         .map_err(|e| format!("读取命令组文件失败: {}", e))?;
     
-    let groups: Vec<CommandGroup> = serde_json::from_str(&content)
-        .map_err(|e| format!("解析命令组文件失败: {}", e))?;
-    
-    Ok(groups)
+    serde_json::from_str(&content)
+        .map_err(|e| format!("解析命令组文件失败: {}", e))
+}
+
+/// 获取所有命令组
+#[tauri::command]
+pub fn list_command_groups<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<Vec<CommandGroup>, String> {
+    read_command_groups(&app_handle)
 }
 
 /// 保存命令组
@@ -3,9 +3,17 @@ mod config;
 mod log;
 mod export;
 mod parser;
+mod sniffer;
+mod profile;
+mod autoconnect;
+mod modbus;
 
 pub use serial::*;
 pub use config::*;
 pub use log::*;
 pub use export::*;
 pub use parser::*;
+pub use sniffer::*;
+pub use profile::*;
+pub use autoconnect::*;
+pub use modbus::*;
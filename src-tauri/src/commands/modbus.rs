@@ -0,0 +1,53 @@
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime, State};
+use crate::models::DataDirection;
+use crate::parser::{EncodedFrame, ModbusMode, ModbusRequest, ModbusTransaction, ParserState};
+use crate::serial::{log_data, ModbusMaster, SerialManager};
+
+/// 默认响应超时（毫秒）
+const DEFAULT_TIMEOUT_MS: u64 = 1000;
+
+/// 构造 Modbus 请求帧，用于发送前预览
+#[tauri::command]
+pub async fn encode_modbus_request(
+    mode: ModbusMode,
+    request: ModbusRequest,
+) -> Result<EncodedFrame, String> {
+    request.encode(mode).map(EncodedFrame::new)
+}
+
+/// Modbus 主站请求：读写线圈与寄存器并等待从站响应
+///
+/// 广播请求（从站地址 0）发出后不等待响应，返回 `None`。
+/// 等待响应会阻塞线程，因此放到阻塞线程池中执行。
+#[tauri::command]
+pub async fn modbus_request<R: Runtime>(
+    app_handle: AppHandle<R>,
+    mode: ModbusMode,
+    request: ModbusRequest,
+    timeout_ms: Option<u64>,
+) -> Result<Option<ModbusTransaction>, String> {
+    let timeout = Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS));
+    tauri::async_runtime::spawn_blocking(move || {
+        let manager = app_handle.state::<SerialManager>();
+        let master = app_handle.state::<ModbusMaster>();
+        master.transact(mode, &request, timeout, |adu| {
+            manager.send(adu)?;
+            log_data(&app_handle, adu, DataDirection::Tx);
+            Ok(())
+        })
+    })
+    .await
+    .map_err(|e| format!("Modbus request task failed: {}", e))?
+}
+
+/// 清除内置 Modbus 解码器中等待配对的请求
+#[tauri::command]
+pub async fn reset_modbus_pairing(
+    state: State<'_, ParserState>,
+) -> Result<(), String> {
+    state.modbus.lock()
+        .map_err(|e| format!("Failed to lock parser: {}", e))?
+        .reset();
+    Ok(())
+}
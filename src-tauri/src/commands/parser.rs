@@ -1,5 +1,54 @@
-use crate::parser::{Protocol, ParsedFrame, ParserState};
-use tauri::State;
+use crate::parser::{
+    encode_frame as build_frame, read_protocol_file, validate_protocol as check_definition,
+    write_protocol_file, ChecksumType, CrcSpec, Diagnostic, EncodedFrame, ImportConflict,
+    ImportReport, Protocol, ParsedFrame, ParserState, ProtocolParser, Severity,
+};
+use crate::models::DataDirection;
+use crate::serial::{log_data, SerialManager};
+use crate::storage::{DatabaseManager, DbState};
+use std::fs;
+use tauri::{AppHandle, Runtime, State};
+
+/// 从数据库恢复协议及激活协议
+pub(crate) fn restore_protocols(db: &DatabaseManager, state: &ParserState) -> Result<(), String> {
+    let protocols = db.load_protocols()?;
+    let active = db.get_active_protocol()?;
+    let auto_detect = db.get_auto_detect()?;
+
+    let mut parser = state.parser.lock()
+        .map_err(|e| format!("Failed to lock parser: {}", e))?;
+
+    for protocol in protocols {
+        parser.register_protocol(protocol);
+    }
+    if let Some(id) = active.filter(|id| parser.get_protocol(id).is_some()) {
+        parser.set_active_protocol(Some(id));
+    }
+    parser.set_auto_detect(auto_detect);
+    Ok(())
+}
+
+/// 写入数据库，失败时返回警告而不中断操作
+///
+/// 数据库未初始化或无法打开时，内存中的协议设置照常生效，只是重启后不会保留。
+pub(crate) fn persist(db_state: &DbState, save: impl FnOnce(&DatabaseManager) -> Result<(), String>) -> Option<String> {
+    let result = db_state.manager.lock()
+        .map_err(|e| format!("Failed to lock database: {}", e))
+        .and_then(|db| save(&db));
+    result.err().map(|e| format!("已生效，但未能保存到数据库: {}", e))
+}
+
+/// 保存协议到数据库，返回写入失败的警告及是否已保存
+///
+/// 版本冲突（库中已有更新的版本）不属于写入失败，以 `false` 返回，由调用方拒绝该协议。
+fn save_to_database(db_state: &DbState, protocol: &Protocol) -> (Option<String>, bool) {
+    let mut saved = true;
+    let warning = persist(db_state, |db| {
+        saved = db.save_protocol(protocol)?;
+        Ok(())
+    });
+    (warning, saved)
+}
 
 /// 获取所有协议
 #[tauri::command]
@@ -13,42 +62,125 @@ pub async fn list_protocols(
 }
 
 /// 保存协议
+///
+/// 库中已有更新的版本时拒绝保存；其余情况协议总是注册到解析器，写入数据库失败时返回警告。
 #[tauri::command]
 pub async fn save_protocol(
     state: State<'_, ParserState>,
+    db_state: State<'_, DbState>,
     protocol: Protocol,
-) -> Result<(), String> {
+) -> Result<Option<String>, String> {
+    let errors: Vec<String> = check_definition(&protocol)
+        .into_iter()
+        .filter(|d| d.severity == Severity::Error)
+        .map(|d| match d.field {
+            Some(field) => format!("字段 {}: {}", field, d.message),
+            None => d.message,
+        })
+        .collect();
+    if !errors.is_empty() {
+        return Err(errors.join("; "));
+    }
+    
+    let (warning, saved) = save_to_database(&db_state, &protocol);
+    if !saved {
+        return Err(format!("协议 {} 已有更新的版本", protocol.name));
+    }
+    
     let mut parser = state.parser.lock()
         .map_err(|e| format!("Failed to lock parser: {}", e))?;
     
     parser.register_protocol(protocol);
-    Ok(())
+    Ok(warning)
 }
 
-/// 删除协议
+/// 校验协议定义
+#[tauri::command]
+pub async fn validate_protocol(
+    protocol: Protocol,
+) -> Result<Vec<Diagnostic>, String> {
+    Ok(check_definition(&protocol))
+}
+
+/// 删除协议，写入数据库失败时返回警告
 #[tauri::command]
 pub async fn delete_protocol(
     state: State<'_, ParserState>,
+    db_state: State<'_, DbState>,
     id: String,
-) -> Result<(), String> {
+) -> Result<Option<String>, String> {
     let mut parser = state.parser.lock()
         .map_err(|e| format!("Failed to lock parser: {}", e))?;
+    let was_active = parser.active_protocol().is_some_and(|p| p.id == id);
+    
+    let warning = persist(&db_state, |db| {
+        db.delete_protocol(&id)?;
+        if was_active {
+            db.save_active_protocol(None)?;
+        }
+        Ok(())
+    });
     
     parser.remove_protocol(&id);
-    Ok(())
+    Ok(warning)
 }
 
-/// 设置激活的协议
+/// 设置激活的协议，写入数据库失败时返回警告
 #[tauri::command]
 pub async fn set_active_protocol(
     state: State<'_, ParserState>,
+    db_state: State<'_, DbState>,
     id: Option<String>,
-) -> Result<(), String> {
+) -> Result<Option<String>, String> {
     let mut parser = state.parser.lock()
         .map_err(|e| format!("Failed to lock parser: {}", e))?;
     
+    let warning = persist(&db_state, |db| db.save_active_protocol(id.as_deref()));
+    
     parser.set_active_protocol(id);
-    Ok(())
+    Ok(warning)
+}
+
+/// 获取是否启用协议自动识别
+#[tauri::command]
+pub async fn get_protocol_auto_detect(
+    state: State<'_, ParserState>,
+) -> Result<bool, String> {
+    let parser = state.parser.lock()
+        .map_err(|e| format!("Failed to lock parser: {}", e))?;
+    
+    Ok(parser.auto_detect())
+}
+
+/// 设置协议自动识别模式
+///
+/// 启用后解析与流式分帧在全部协议中匹配，激活协议被忽略但仍保留。
+/// 写入数据库失败时返回警告。
+#[tauri::command]
+pub async fn set_protocol_auto_detect(
+    state: State<'_, ParserState>,
+    db_state: State<'_, DbState>,
+    enabled: bool,
+) -> Result<Option<String>, String> {
+    let mut parser = state.parser.lock()
+        .map_err(|e| format!("Failed to lock parser: {}", e))?;
+    
+    let warning = persist(&db_state, |db| db.save_auto_detect(enabled));
+    
+    parser.set_auto_detect(enabled);
+    Ok(warning)
+}
+
+/// 在全部协议中识别数据
+#[tauri::command]
+pub async fn detect_protocol(
+    state: State<'_, ParserState>,
+    data: Vec<u8>,
+) -> Result<Option<ParsedFrame>, String> {
+    let parser = state.parser.lock()
+        .map_err(|e| format!("Failed to lock parser: {}", e))?;
+    
+    Ok(parser.detect(&data))
 }
 
 /// 解析数据
@@ -78,3 +210,158 @@ pub async fn parse_data_with_protocol(
     
     Ok(parser.parse_with_protocol(&data, protocol))
 }
+
+/// 按协议与字段值编码一帧
+///
+/// 返回的十六进制文本可直接作为命令数据保存。
+#[tauri::command]
+pub async fn encode_frame(
+    state: State<'_, ParserState>,
+    protocol_id: String,
+    values: serde_json::Map<String, serde_json::Value>,
+) -> Result<EncodedFrame, String> {
+    let parser = state.parser.lock()
+        .map_err(|e| format!("Failed to lock parser: {}", e))?;
+    
+    let protocol = parser.get_protocol(&protocol_id)
+        .ok_or("Protocol not found")?;
+    
+    build_frame(protocol, &values).map(EncodedFrame::new)
+}
+
+/// 按协议编码一帧并发送
+#[tauri::command]
+pub fn send_frame<R: Runtime>(
+    app_handle: AppHandle<R>,
+    manager: State<'_, SerialManager>,
+    state: State<'_, ParserState>,
+    protocol_id: String,
+    values: serde_json::Map<String, serde_json::Value>,
+) -> Result<EncodedFrame, String> {
+    let data = {
+        let parser = state.parser.lock()
+            .map_err(|e| format!("Failed to lock parser: {}", e))?;
+        let protocol = parser.get_protocol(&protocol_id)
+            .ok_or("Protocol not found")?;
+        build_frame(protocol, &values)?
+    };
+    
+    manager.send(&data)?;
+    log_data(&app_handle, &data, DataDirection::Tx);
+    Ok(EncodedFrame::new(data))
+}
+
+/// 导出协议到文件
+#[tauri::command]
+pub async fn export_protocol(
+    state: State<'_, ParserState>,
+    ids: Vec<String>,
+    path: String,
+) -> Result<(), String> {
+    let protocols = {
+        let parser = state.parser.lock()
+            .map_err(|e| format!("Failed to lock parser: {}", e))?;
+        ids.iter()
+            .map(|id| parser.get_protocol(id).cloned().ok_or_else(|| format!("协议不存在: {}", id)))
+            .collect::<Result<Vec<_>, _>>()?
+    };
+    
+    let content = write_protocol_file(protocols)?;
+    fs::write(&path, content)
+        .map_err(|e| format!("写入协议文件失败: {}", e))
+}
+
+/// 从文件导入协议
+///
+/// 先校验文件中的全部协议，再按 ID 或名称检测冲突并按 `conflict` 处理。
+/// 写入数据库失败的协议仍在内存中生效，原因记录在结果的 `warnings` 中。
+#[tauri::command]
+pub async fn import_protocol(
+    state: State<'_, ParserState>,
+    db_state: State<'_, DbState>,
+    path: String,
+    conflict: Option<ImportConflict>,
+) -> Result<ImportReport, String> {
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("读取协议文件失败: {}", e))?;
+    let imported = read_protocol_file(&content)?;
+    let conflict = conflict.unwrap_or_default();
+    
+    let mut parser = state.parser.lock()
+        .map_err(|e| format!("Failed to lock parser: {}", e))?;
+    
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut report = ImportReport::default();
+    
+    for mut protocol in imported {
+        let existing = parser.get_protocol(&protocol.id)
+            .or_else(|| parser.get_protocols().into_iter().find(|p| p.name == protocol.name))
+            .map(|p| p.id.clone());
+        
+        let replaced = match (existing, &conflict) {
+            (None, _) => false,
+            (Some(_), ImportConflict::Skip) => {
+                report.skipped.push(protocol.name.clone());
+                continue;
+            }
+            (Some(id), ImportConflict::Overwrite) => {
+                protocol.id = id;
+                protocol.updated_at = now;
+                true
+            }
+            (Some(_), ImportConflict::KeepBoth) => {
+                protocol.id = uuid::Uuid::new_v4().to_string();
+                protocol.name = unique_protocol_name(&parser, &protocol.name);
+                protocol.created_at = now;
+                protocol.updated_at = now;
+                false
+            }
+        };
+        
+        // 逐个保存，单个协议写入失败不影响其余协议的导入
+        let (warning, saved) = save_to_database(&db_state, &protocol);
+        if !saved {
+            report.failed.push(format!("{}: 已有更新的版本", protocol.name));
+            continue;
+        }
+        if let Some(warning) = warning {
+            report.warnings.push(format!("{}: {}", protocol.name, warning));
+        }
+        if replaced {
+            report.replaced.push(protocol.name.clone());
+        } else {
+            report.added.push(protocol.name.clone());
+        }
+        parser.register_protocol(protocol);
+    }
+    
+    Ok(report)
+}
+
+/// 生成不与已有协议重名的名称
+fn unique_protocol_name(parser: &ProtocolParser, name: &str) -> String {
+    let exists = |candidate: &str| parser.get_protocols().iter().any(|p| p.name == candidate);
+    (2..)
+        .map(|n| format!("{} ({})", name, n))
+        .find(|candidate| !exists(candidate))
+        .unwrap_or_else(|| name.to_string())
+}
+
+/// 获取 CRC 预置参数列表
+#[tauri::command]
+pub async fn list_crc_presets() -> Result<Vec<CrcSpec>, String> {
+    Ok(CrcSpec::presets())
+}
+
+/// 计算校验值，返回十六进制字符串
+#[tauri::command]
+pub async fn compute_checksum(
+    data: Vec<u8>,
+    checksum_type: ChecksumType,
+) -> Result<String, String> {
+    if let ChecksumType::Crc(spec) = &checksum_type {
+        spec.validate()?;
+    }
+    
+    Ok(format!("{:0width$X}", checksum_type.compute(&data), width = checksum_type.size() * 2))
+}
@@ -0,0 +1,245 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use crate::models::*;
+use crate::parser::ParserState;
+use crate::serial::SerialManager;
+use crate::storage::DbState;
+use super::{open_session, persist};
+
+/// 按连接配置打开会话的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenedProfile {
+    /// 打开的连接配置
+    pub profile: ConnectionProfile,
+    /// 激活协议未能保存到数据库时的警告
+    pub warning: Option<String>,
+}
+
+/// 获取连接配置存储路径
+fn get_profiles_path(app_handle: &AppHandle<impl Runtime>) -> PathBuf {
+    let app_data_dir = app_handle.path().app_data_dir()
+        .expect("无法获取应用数据目录");
+    
+    fs::create_dir_all(&app_data_dir).ok();
+    
+    app_data_dir.join("profiles.json")
+}
+
+/// 读取所有连接配置
+pub(crate) fn read_profiles(app_handle: &AppHandle<impl Runtime>) -> Result<Vec<ConnectionProfile>, String> {
+    let profiles_path = get_profiles_path(app_handle);
+    
+    if !profiles_path.exists() {
+        return Ok(Vec::new());
+    }
+    
+    let content = fs::read_to_string(&profiles_path)
+        .map_err(|e| format!("读取连接配置文件失败: {}", e))?;
+    
+    serde_json::from_str(&content)
+        .map_err(|e| format!("解析连接配置文件失败: {}", e))
+}
+
+/// 写入所有连接配置
+fn write_profiles(app_handle: &AppHandle<impl Runtime>, profiles: &[ConnectionProfile]) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(profiles)
+        .map_err(|e| format!("序列化连接配置失败: {}", e))?;
+    
+    fs::write(get_profiles_path(app_handle), content)
+        .map_err(|e| format!("写入连接配置文件失败: {}", e))
+}
+
+/// 按名称新增或替换连接配置
+fn upsert_profile(profiles: &mut Vec<ConnectionProfile>, profile: ConnectionProfile) {
+    if let Some(existing) = profiles.iter_mut().find(|p| p.name == profile.name) {
+        *existing = profile;
+    } else {
+        profiles.push(profile);
+    }
+}
+
+/// 获取所有连接配置
+#[tauri::command]
+pub fn list_profiles<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<Vec<ConnectionProfile>, String> {
+    read_profiles(&app_handle)
+}
+
+/// 保存连接配置
+#[tauri::command]
+pub fn save_profile<R: Runtime>(
+    app_handle: AppHandle<R>,
+    mut profile: ConnectionProfile,
+) -> Result<(), String> {
+    if profile.name.trim().is_empty() {
+        return Err("连接配置名称不能为空".to_string());
+    }
+    
+    let mut profiles = read_profiles(&app_handle)?;
+    
+    profile.updated_at = chrono::Utc::now().timestamp_millis();
+    upsert_profile(&mut profiles, profile);
+    
+    write_profiles(&app_handle, &profiles)
+}
+
+/// 删除连接配置
+#[tauri::command]
+pub fn delete_profile<R: Runtime>(
+    app_handle: AppHandle<R>,
+    name: String,
+) -> Result<(), String> {
+    let mut profiles = read_profiles(&app_handle)?;
+    
+    profiles.retain(|p| p.name != name);
+    
+    write_profiles(&app_handle, &profiles)
+}
+
+/// 复制连接配置
+#[tauri::command]
+pub fn duplicate_profile<R: Runtime>(
+    app_handle: AppHandle<R>,
+    name: String,
+    new_name: String,
+) -> Result<ConnectionProfile, String> {
+    let mut profiles = read_profiles(&app_handle)?;
+    
+    if profiles.iter().any(|p| p.name == new_name) {
+        return Err(format!("连接配置已存在: {}", new_name));
+    }
+    
+    let mut copy = profiles.iter()
+        .find(|p| p.name == name)
+        .cloned()
+        .ok_or_else(|| format!("未找到连接配置: {}", name))?;
+    
+    let now = chrono::Utc::now().timestamp_millis();
+    copy.name = new_name;
+    copy.created_at = now;
+    copy.updated_at = now;
+    
+    profiles.push(copy.clone());
+    write_profiles(&app_handle, &profiles)?;
+    
+    Ok(copy)
+}
+
+/// 导入连接配置（同名配置将被覆盖）
+///
+/// 与保存时一致，名称不能为空；导入数据中名称重复时整体拒绝。
+#[tauri::command]
+pub fn import_profiles<R: Runtime>(
+    app_handle: AppHandle<R>,
+    json: String,
+) -> Result<Vec<ConnectionProfile>, String> {
+    let imported: Vec<ConnectionProfile> = serde_json::from_str(&json)
+        .map_err(|e| format!("解析导入数据失败: {}", e))?;
+    
+    let mut names = HashSet::new();
+    for profile in &imported {
+        if profile.name.trim().is_empty() {
+            return Err("连接配置名称不能为空".to_string());
+        }
+        if !names.insert(profile.name.as_str()) {
+            return Err(format!("导入数据中连接配置名称重复: {}", profile.name));
+        }
+    }
+    
+    let mut profiles = read_profiles(&app_handle)?;
+    
+    for profile in imported.iter().cloned() {
+        upsert_profile(&mut profiles, profile);
+    }
+    
+    write_profiles(&app_handle, &profiles)?;
+    
+    Ok(imported)
+}
+
+/// 导出连接配置
+#[tauri::command]
+pub fn export_profiles<R: Runtime>(
+    app_handle: AppHandle<R>,
+    names: Vec<String>,
+) -> Result<String, String> {
+    let exported: Vec<ConnectionProfile> = read_profiles(&app_handle)?
+        .into_iter()
+        .filter(|p| names.contains(&p.name))
+        .collect();
+    
+    serde_json::to_string_pretty(&exported)
+        .map_err(|e| format!("序列化导出数据失败: {}", e))
+}
+
+/// 按连接配置打开会话
+///
+/// 解析端口、切换激活协议并以档案中的参数、接收编码与分帧方式打开会话；
+/// 命令组由前端根据返回的档案显示。
+#[tauri::command]
+pub fn open_profile<R: Runtime>(
+    app_handle: AppHandle<R>,
+    manager: State<'_, SerialManager>,
+    parser_state: State<'_, ParserState>,
+    name: String,
+) -> Result<OpenedProfile, String> {
+    let profile = read_profiles(&app_handle)?
+        .into_iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| format!("未找到连接配置: {}", name))?;
+    
+    let warning = apply_profile(&app_handle, &manager, &parser_state, &profile, None)?;
+    
+    Ok(OpenedProfile { profile, warning })
+}
+
+/// 以连接配置切换激活协议并打开会话（含接收编码与分帧方式）
+///
+/// 激活协议先于会话切换，打开后收到的首批数据即按档案的协议分帧；打开失败时恢复原激活协议。
+/// `port_name` 不为空时直接使用该端口，否则按档案的选择条件查找。
+/// 激活协议写入数据库失败时返回警告。
+pub(crate) fn apply_profile<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    manager: &SerialManager,
+    parser_state: &ParserState,
+    profile: &ConnectionProfile,
+    port_name: Option<String>,
+) -> Result<Option<String>, String> {
+    let mut config = profile.config.clone();
+    config.port_name = match port_name {
+        Some(name) => name,
+        None => SerialManager::resolve_port(&profile.port)?,
+    };
+    
+    // 切换档案时先关闭当前会话
+    if manager.status() == SerialStatus::Open {
+        manager.close()?;
+        let _ = app_handle.emit("serial:status-changed", &SerialStatus::Closed);
+    }
+    
+    let previous = {
+        let mut parser = parser_state.parser.lock()
+            .map_err(|e| format!("Failed to lock parser: {}", e))?;
+        let previous = parser.active_protocol().map(|p| p.id.clone());
+        parser.set_active_protocol(profile.active_protocol.clone());
+        previous
+    };
+    
+    if let Err(e) = open_session(app_handle, manager, config, profile.receive_mode()) {
+        if let Ok(mut parser) = parser_state.parser.lock() {
+            parser.set_active_protocol(previous);
+        }
+        return Err(e);
+    }
+    
+    // 与手动切换一致，激活协议写入数据库以便重启后恢复
+    let warning = app_handle.try_state::<DbState>()
+        .and_then(|db_state| persist(&db_state, |db| db.save_active_protocol(profile.active_protocol.as_deref())));
+    
+    Ok(warning)
+}
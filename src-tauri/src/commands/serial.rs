@@ -1,6 +1,7 @@
 use tauri::{AppHandle, Emitter, Runtime, State};
 use crate::models::*;
-use crate::serial::{SerialManager, parse_hex_string};
+use crate::parser::{parse_hex_string, ByteOrder, ChecksumType};
+use crate::serial::{SerialManager, log_data};
 
 /// 列出可用串口
 #[tauri::command]
@@ -15,10 +16,25 @@ pub fn open_port<R: Runtime>(
     manager: State<'_, SerialManager>,
     config: SerialConfig,
 ) -> Result<(), String> {
-    manager.open(config.clone())?;
+    open_session(&app_handle, &manager, config, ReceiveMode::default())
+}
+
+/// 打开会话并以指定接收方式启动读取任务
+pub(crate) fn open_session<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    manager: &SerialManager,
+    config: SerialConfig,
+    mode: ReceiveMode,
+) -> Result<(), String> {
+    manager.open(config)?;
     
     // 启动读取任务
-    manager.start_read_task(app_handle.clone());
+    manager.start_read_task(app_handle.clone(), mode);
+    
+    // 通知前端虚拟串口路径，供外部程序连接
+    if let Some(path) = manager.virtual_path() {
+        let _ = app_handle.emit("serial:virtual-port", &path);
+    }
     
     // 发送状态变化事件
     let _ = app_handle.emit("serial:status-changed", &SerialStatus::Open);
@@ -41,19 +57,33 @@ pub fn close_port<R: Runtime>(
 }
 
 /// 发送数据
+///
+/// 指定 `append_checksum` 时在数据末尾追加校验值，字节序默认大端。
 #[tauri::command]
-pub fn send_data(
+pub fn send_data<R: Runtime>(
+    app_handle: AppHandle<R>,
     manager: State<'_, SerialManager>,
     data: String,
     format: String,
+    append_checksum: Option<ChecksumType>,
+    checksum_byte_order: Option<ByteOrder>,
 ) -> Result<(), String> {
-    let bytes = match format.as_str() {
+    let mut bytes = match format.as_str() {
         "hex" => parse_hex_string(&data)?,
         "ascii" => data.into_bytes(),
         _ => return Err("不支持的格式".to_string()),
     };
 
-    manager.send(&bytes)
+    if let Some(checksum) = append_checksum {
+        if let ChecksumType::Crc(spec) = &checksum {
+            spec.validate()?;
+        }
+        checksum.append(&mut bytes, &checksum_byte_order.unwrap_or_default());
+    }
+
+    manager.send(&bytes)?;
+    log_data(&app_handle, &bytes, DataDirection::Tx);
+    Ok(())
 }
 
 /// 获取串口状态
@@ -63,3 +93,11 @@ pub fn get_status(
 ) -> Result<SerialStatus, String> {
     Ok(manager.status())
 }
+
+/// 获取虚拟串口路径（仅 PTY 会话）
+#[tauri::command]
+pub fn get_virtual_port(
+    manager: State<'_, SerialManager>,
+) -> Result<Option<String>, String> {
+    Ok(manager.virtual_path())
+}
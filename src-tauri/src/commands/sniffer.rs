@@ -0,0 +1,40 @@
+use tauri::{AppHandle, Emitter, Runtime, State};
+use crate::models::*;
+use crate::serial::SnifferManager;
+
+/// 启动双串口嗅探
+#[tauri::command]
+pub fn start_sniffer<R: Runtime>(
+    app_handle: AppHandle<R>,
+    sniffer: State<'_, SnifferManager>,
+    config: SnifferConfig,
+) -> Result<(), String> {
+    sniffer.start(config, app_handle.clone())?;
+
+    // 发送状态变化事件
+    let _ = app_handle.emit("sniffer:status-changed", &sniffer.status());
+
+    Ok(())
+}
+
+/// 停止双串口嗅探
+#[tauri::command]
+pub fn stop_sniffer<R: Runtime>(
+    app_handle: AppHandle<R>,
+    sniffer: State<'_, SnifferManager>,
+) -> Result<(), String> {
+    sniffer.stop()?;
+
+    // 发送状态变化事件
+    let _ = app_handle.emit("sniffer:status-changed", &sniffer.status());
+
+    Ok(())
+}
+
+/// 获取嗅探状态
+#[tauri::command]
+pub fn get_sniffer_status(
+    sniffer: State<'_, SnifferManager>,
+) -> Result<SnifferStatus, String> {
+    Ok(sniffer.status())
+}
@@ -4,7 +4,7 @@ mod commands;
 mod storage;
 mod parser;
 
-use serial::SerialManager;
+use serial::{spawn_port_watcher, ModbusMaster, SerialManager, SnifferManager};
 use storage::{DatabaseManager, DbState, LogManager, LogState, LogConfig};
 use parser::ParserState;
 use tauri::Manager;
@@ -19,6 +19,12 @@ pub fn run() {
             let serial_manager = SerialManager::new();
             app.manage(serial_manager);
             
+            // 初始化嗅探管理器
+            app.manage(SnifferManager::new());
+            
+            // 初始化 Modbus 主站
+            app.manage(ModbusMaster::new());
+            
             // 初始化数据库管理器
             let db_path = DatabaseManager::default_path(app.handle());
             let mut db_manager = DatabaseManager::new(db_path);
@@ -26,6 +32,12 @@ pub fn run() {
                 eprintln!("Warning: Failed to initialize database: {}. Some features may not work.", e);
                 // 继续运行，但数据库功能可能不可用
             }
+            
+            // 初始化协议解析器，恢复已保存的协议
+            let parser_state = ParserState::new();
+            if let Err(e) = commands::restore_protocols(&db_manager, &parser_state) {
+                eprintln!("Warning: Failed to restore protocols: {}", e);
+            }
             app.manage(DbState::new(db_manager));
             
             // 初始化日志管理器
@@ -33,8 +45,14 @@ pub fn run() {
             let log_manager = LogManager::new(log_config);
             app.manage(LogState::new(log_manager));
             
-            // 初始化协议解析器
-            app.manage(ParserState::new());
+            // 注册协议解析器
+            app.manage(parser_state);
+            
+            // 启动串口监视，按自动连接规则接入新设备
+            let app_handle = app.handle().clone();
+            spawn_port_watcher(1000, move |change| {
+                commands::handle_port_change(&app_handle, change);
+            });
             
             Ok(())
         })
@@ -45,6 +63,11 @@ pub fn run() {
             commands::close_port,
             commands::send_data,
             commands::get_status,
+            commands::get_virtual_port,
+            // 嗅探命令
+            commands::start_sniffer,
+            commands::stop_sniffer,
+            commands::get_sniffer_status,
             // 配置命令
             commands::get_settings,
             commands::save_settings,
@@ -53,6 +76,18 @@ pub fn run() {
             commands::delete_command_group,
             commands::import_commands,
             commands::export_commands,
+            // 连接配置命令
+            commands::list_profiles,
+            commands::save_profile,
+            commands::delete_profile,
+            commands::duplicate_profile,
+            commands::import_profiles,
+            commands::export_profiles,
+            commands::open_profile,
+            // 自动连接命令
+            commands::list_autoconnect_rules,
+            commands::save_autoconnect_rule,
+            commands::delete_autoconnect_rule,
             // 日志命令
             commands::start_logging,
             commands::stop_logging,
@@ -72,10 +107,24 @@ pub fn run() {
             // 解析器命令
             commands::list_protocols,
             commands::save_protocol,
+            commands::validate_protocol,
             commands::delete_protocol,
             commands::set_active_protocol,
+            commands::get_protocol_auto_detect,
+            commands::set_protocol_auto_detect,
+            commands::detect_protocol,
             commands::parse_data,
             commands::parse_data_with_protocol,
+            commands::encode_frame,
+            commands::send_frame,
+            commands::export_protocol,
+            commands::import_protocol,
+            commands::list_crc_presets,
+            commands::compute_checksum,
+            // Modbus 命令
+            commands::encode_modbus_request,
+            commands::modbus_request,
+            commands::reset_modbus_pairing,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
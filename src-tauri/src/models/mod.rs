@@ -1,7 +1,9 @@
 mod serial;
 mod command;
 mod settings;
+mod profile;
 
 pub use serial::*;
 pub use command::*;
 pub use settings::*;
+pub use profile::*;
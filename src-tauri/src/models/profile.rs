@@ -0,0 +1,132 @@
+use serde::{Deserialize, Serialize};
+use super::{DataFormat, PortInfo, SerialConfig};
+
+/// 串口选择方式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum PortSelector {
+    /// 按端口名称（或 WebSocket 地址）选择
+    #[serde(rename_all = "camelCase")]
+    Name { name: String },
+    /// 按 USB VID/PID 及可选序列号选择
+    #[serde(rename_all = "camelCase")]
+    Usb {
+        vid: u16,
+        pid: u16,
+        serial_number: Option<String>,
+    },
+}
+
+impl PortSelector {
+    /// 判断串口是否符合选择条件
+    pub fn matches(&self, port: &PortInfo) -> bool {
+        match self {
+            PortSelector::Name { name } => &port.name == name,
+            PortSelector::Usb { vid, pid, serial_number } => {
+                port.vid == Some(*vid)
+                    && port.pid == Some(*pid)
+                    && serial_number
+                        .as_ref()
+                        .map(|sn| port.serial_number.as_ref() == Some(sn))
+                        .unwrap_or(true)
+            }
+        }
+    }
+}
+
+/// 接收数据分帧方式
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FramingMode {
+    /// 按时间节流分包
+    #[default]
+    Raw,
+    /// 按换行分包
+    Line,
+    /// 按协议定义分帧
+    Protocol,
+}
+
+/// 接收数据的分包与显示方式
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReceiveMode {
+    /// 显示编码
+    pub encoding: DataFormat,
+    /// 分帧方式
+    pub framing: FramingMode,
+}
+
+/// 连接配置档案
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionProfile {
+    /// 档案名称（唯一）
+    pub name: String,
+    /// 串口选择方式
+    pub port: PortSelector,
+    /// 传输类型与线路参数（`port_name` 在打开时由 `port` 解析得到）
+    pub config: SerialConfig,
+    /// 接收数据的显示编码
+    pub encoding: DataFormat,
+    /// 分帧方式
+    #[serde(default)]
+    pub framing: FramingMode,
+    /// 激活的协议 ID
+    pub active_protocol: Option<String>,
+    /// 显示的命令组 ID
+    pub command_group: Option<String>,
+    /// 创建时间
+    pub created_at: i64,
+    /// 更新时间
+    pub updated_at: i64,
+}
+
+impl ConnectionProfile {
+    /// 档案中的接收方式
+    pub fn receive_mode(&self) -> ReceiveMode {
+        ReceiveMode {
+            encoding: self.encoding.clone(),
+            framing: self.framing.clone(),
+        }
+    }
+}
+
+/// 自动连接规则
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoConnectRule {
+    /// 规则 ID
+    pub id: String,
+    /// 规则名称
+    pub name: String,
+    /// 是否启用
+    pub enabled: bool,
+    /// 匹配新出现串口的条件
+    pub selector: PortSelector,
+    /// 打开时使用的连接配置名称，仅传输类型为物理串口的档案会被触发
+    pub profile: String,
+    /// 打开后是否开始记录日志
+    #[serde(default)]
+    pub start_logging: bool,
+    /// 打开后依次发送的命令组 ID
+    pub command_group: Option<String>,
+    /// 命令组中相邻命令的发送间隔（毫秒）
+    #[serde(default = "default_sequence_interval")]
+    pub interval_ms: u64,
+}
+
+fn default_sequence_interval() -> u64 {
+    100
+}
+
+/// 串口变化
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortChange {
+    /// 当前所有串口
+    pub ports: Vec<PortInfo>,
+    /// 新出现的串口
+    pub arrived: Vec<PortInfo>,
+    /// 已移除的串口名称
+    pub removed: Vec<String>,
+}
@@ -16,6 +16,9 @@ pub struct SerialConfig {
     pub parity: Parity,
     /// 流控制
     pub flow_control: FlowControl,
+    /// 传输类型
+    #[serde(default)]
+    pub transport: TransportType,
 }
 
 impl Default for SerialConfig {
@@ -27,10 +30,26 @@ impl Default for SerialConfig {
             stop_bits: StopBits::One,
             parity: Parity::None,
             flow_control: FlowControl::None,
+            transport: TransportType::default(),
         }
     }
 }
 
+/// 传输类型
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TransportType {
+    /// 物理串口
+    #[default]
+    Serial,
+    /// 进程内回环：发送的数据原样回到接收端
+    Loopback,
+    /// 伪终端对（仅 Unix），外部程序可连接从端路径
+    Pty,
+    /// WebSocket 客户端，`port_name` 为 ws:// 地址
+    WebSocket,
+}
+
 /// 数据位
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -124,6 +143,12 @@ pub struct PortInfo {
     pub port_type: String,
     pub manufacturer: Option<String>,
     pub product: Option<String>,
+    /// USB 厂商 ID
+    pub vid: Option<u16>,
+    /// USB 产品 ID
+    pub pid: Option<u16>,
+    /// USB 序列号
+    pub serial_number: Option<String>,
 }
 
 /// 数据包
@@ -133,22 +158,109 @@ pub struct DataPacket {
     pub id: String,
     pub data: String,
     pub timestamp: i64,
+    /// 微秒级时间戳
+    #[serde(default)]
+    pub timestamp_us: i64,
     pub direction: DataDirection,
     pub format: DataFormat,
 }
 
+impl DataPacket {
+    /// 以十六进制格式创建数据包，时间戳取当前时刻
+    pub fn from_bytes(bytes: &[u8], direction: DataDirection) -> Self {
+        Self::encode(bytes, direction, DataFormat::Hex)
+    }
+
+    /// 按指定格式创建数据包，ASCII 格式下无效的 UTF-8 字节显示为替换字符
+    pub fn encode(bytes: &[u8], direction: DataDirection, format: DataFormat) -> Self {
+        let now = chrono::Utc::now();
+        let data = match format {
+            DataFormat::Hex => bytes
+                .iter()
+                .map(|b| format!("{:02X}", b))
+                .collect::<Vec<_>>()
+                .join(" "),
+            DataFormat::Ascii => String::from_utf8_lossy(bytes).to_string(),
+        };
+
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            data,
+            timestamp: now.timestamp_millis(),
+            timestamp_us: now.timestamp_micros(),
+            direction,
+            format,
+        }
+    }
+}
+
 /// 数据方向
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum DataDirection {
     Rx,
     Tx,
+    /// 嗅探模式：A 口发往 B 口
+    #[serde(rename = "a2b")]
+    AToB,
+    /// 嗅探模式：B 口发往 A 口
+    #[serde(rename = "b2a")]
+    BToA,
+}
+
+impl DataDirection {
+    /// 日志中使用的方向标签
+    pub fn label(&self) -> &'static str {
+        match self {
+            DataDirection::Rx => "RX",
+            DataDirection::Tx => "TX",
+            DataDirection::AToB => "A→B",
+            DataDirection::BToA => "B→A",
+        }
+    }
 }
 
 /// 数据格式
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum DataFormat {
+    #[default]
     Hex,
     Ascii,
 }
+
+impl DataFormat {
+    /// 日志中使用的格式标签
+    pub fn label(&self) -> &'static str {
+        match self {
+            DataFormat::Hex => "hex",
+            DataFormat::Ascii => "ascii",
+        }
+    }
+}
+
+/// 嗅探模式配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnifferConfig {
+    /// A 口配置（通常接 MCU）
+    pub port_a: SerialConfig,
+    /// B 口配置（通常接模组）
+    pub port_b: SerialConfig,
+}
+
+/// 嗅探模式状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnifferStatus {
+    /// 是否正在运行
+    pub running: bool,
+    /// A 口名称
+    pub port_a: Option<String>,
+    /// B 口名称
+    pub port_b: Option<String>,
+    /// A→B 已转发字节数
+    pub bytes_a_to_b: u64,
+    /// B→A 已转发字节数
+    pub bytes_b_to_a: u64,
+}
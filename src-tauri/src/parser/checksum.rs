@@ -0,0 +1,86 @@
+use super::crc::CrcSpec;
+use super::protocol::*;
+
+impl ChecksumType {
+    /// 计算数据的校验值
+    pub fn compute(&self, data: &[u8]) -> u64 {
+        match self {
+            ChecksumType::None => 0,
+            ChecksumType::Sum8 => data.iter().fold(0u8, |acc, b| acc.wrapping_add(*b)) as u64,
+            ChecksumType::Sum16 => data.iter().fold(0u16, |acc, b| acc.wrapping_add(*b as u16)) as u64,
+            ChecksumType::Xor8 => data.iter().fold(0u8, |acc, b| acc ^ b) as u64,
+            ChecksumType::Lrc8 => data.iter().fold(0u8, |acc, b| acc.wrapping_add(*b)).wrapping_neg() as u64,
+            // 兼容旧定义，分别等价于 CRC-8/SMBUS、CRC-16/MODBUS、CRC-32/ISO-HDLC
+            ChecksumType::Crc8 => CrcSpec::new(8, 0x07, 0x00, false, false, 0x00).compute(data),
+            ChecksumType::Crc16 => CrcSpec::new(16, 0x8005, 0xFFFF, true, true, 0x0000).compute(data),
+            ChecksumType::Crc32 => CrcSpec::new(32, 0x04C1_1DB7, 0xFFFF_FFFF, true, true, 0xFFFF_FFFF).compute(data),
+            ChecksumType::Crc(spec) => spec.compute(data),
+        }
+    }
+
+    /// 计算校验值并按字节序追加到数据末尾
+    pub fn append(&self, data: &mut Vec<u8>, byte_order: &ByteOrder) {
+        let value = self.compute(data);
+        data.extend(write_uint(value, self.size(), byte_order));
+    }
+}
+
+/// 按字节序读取无符号整数
+pub(crate) fn read_uint(bytes: &[u8], byte_order: &ByteOrder) -> u64 {
+    match byte_order {
+        ByteOrder::BigEndian => bytes.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64),
+        ByteOrder::LittleEndian => bytes.iter().rev().fold(0u64, |acc, b| (acc << 8) | *b as u64),
+    }
+}
+
+/// 按字节序写出指定长度的无符号整数
+pub(crate) fn write_uint(value: u64, size: usize, byte_order: &ByteOrder) -> Vec<u8> {
+    let be = value.to_be_bytes();
+    let mut bytes = be[8 - size.min(8)..].to_vec();
+    if *byte_order == ByteOrder::LittleEndian {
+        bytes.reverse();
+    }
+    bytes
+}
+
+/// 校验帧数据
+///
+/// 返回 `None` 表示协议未配置校验；校验位置或范围超出帧长度时返回错误信息。
+pub fn verify_checksum(data: &[u8], protocol: &Protocol) -> Option<Result<ChecksumResult, String>> {
+    let checksum_type = protocol.checksum.as_ref()?;
+    if *checksum_type == ChecksumType::None {
+        return None;
+    }
+
+    let size = checksum_type.size();
+    let footer_len = protocol.footer.as_ref().map(|f| f.len()).unwrap_or(0);
+    let layout = protocol.checksum_layout.clone().unwrap_or_default();
+
+    // 校验值默认紧邻帧尾之前
+    let position = match layout.position {
+        Some(position) => position,
+        None => match data.len().checked_sub(footer_len + size) {
+            Some(position) => position,
+            None => return Some(Err("数据长度不足".to_string())),
+        },
+    };
+    if position + size > data.len() {
+        return Some(Err("校验值位置超出帧长度".to_string()));
+    }
+
+    // 参与计算的范围默认到校验值之前
+    let end = layout.end.unwrap_or(position);
+    if layout.start > end || end > data.len() {
+        return Some(Err("校验范围超出帧长度".to_string()));
+    }
+
+    let expected = checksum_type.compute(&data[layout.start..end]);
+    let actual = read_uint(&data[position..position + size], &layout.byte_order);
+
+    Some(Ok(ChecksumResult {
+        checksum_type: checksum_type.clone(),
+        expected,
+        actual,
+        valid: expected == actual,
+    }))
+}
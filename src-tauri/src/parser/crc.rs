@@ -0,0 +1,272 @@
+use serde::{Deserialize, Serialize};
+
+/// CRC 参数（Rocksoft 模型）
+///
+/// 支持 8 到 64 位宽度；多项式、初值和结果异或值以十六进制字符串序列化，
+/// 避免 64 位数值在前端丢失精度。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CrcSpec {
+    /// 预置名称，自定义参数时可为空
+    pub name: Option<String>,
+    /// 位宽
+    pub width: u8,
+    /// 生成多项式（不含最高位）
+    #[serde(with = "hex_u64")]
+    pub poly: u64,
+    /// 初始值
+    #[serde(with = "hex_u64")]
+    pub init: u64,
+    /// 输入字节是否反射
+    pub refin: bool,
+    /// 输出是否反射
+    pub refout: bool,
+    /// 结果异或值
+    #[serde(with = "hex_u64")]
+    pub xorout: u64,
+    /// "123456789" 的校验值，用于核对参数
+    #[serde(default, with = "hex_u64_opt")]
+    pub check: Option<u64>,
+}
+
+impl CrcSpec {
+    /// 创建自定义 CRC 参数
+    pub fn new(width: u8, poly: u64, init: u64, refin: bool, refout: bool, xorout: u64) -> Self {
+        Self {
+            name: None,
+            width,
+            poly,
+            init,
+            refin,
+            refout,
+            xorout,
+            check: None,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn named(name: &str, width: u8, poly: u64, init: u64, refin: bool, refout: bool, xorout: u64, check: u64) -> Self {
+        Self {
+            name: Some(name.to_string()),
+            check: Some(check),
+            ..Self::new(width, poly, init, refin, refout, xorout)
+        }
+    }
+
+    /// 校验参数是否合法
+    pub fn validate(&self) -> Result<(), String> {
+        if !(8..=64).contains(&self.width) {
+            return Err(format!("CRC 位宽必须在 8 到 64 之间: {}", self.width));
+        }
+        if let Some(check) = self.check {
+            let actual = self.compute(b"123456789");
+            if actual != check {
+                return Err(format!("CRC 参数与校验值不符: 期望 0x{:X}, 实际 0x{:X}", check, actual));
+            }
+        }
+        Ok(())
+    }
+
+    /// 校验值字节长度
+    pub fn size(&self) -> usize {
+        (self.width as usize).div_ceil(8)
+    }
+
+    fn mask(&self) -> u64 {
+        if self.width >= 64 {
+            u64::MAX
+        } else {
+            (1u64 << self.width) - 1
+        }
+    }
+
+    /// 计算 CRC
+    pub fn compute(&self, data: &[u8]) -> u64 {
+        let width = self.width.clamp(8, 64);
+        let mask = self.mask();
+        let poly = self.poly & mask;
+        let top_bit = 1u64 << (width - 1);
+        let mut crc = self.init & mask;
+
+        for &byte in data {
+            let byte = if self.refin { byte.reverse_bits() } else { byte };
+            crc ^= (byte as u64) << (width - 8);
+            for _ in 0..8 {
+                crc = if crc & top_bit != 0 {
+                    (crc << 1) ^ poly
+                } else {
+                    crc << 1
+                };
+            }
+            crc &= mask;
+        }
+
+        if self.refout {
+            crc = crc.reverse_bits() >> (64 - width as u32);
+        }
+
+        (crc ^ self.xorout) & mask
+    }
+
+    /// 按名称查找预置参数（不区分大小写）
+    pub fn preset(name: &str) -> Option<CrcSpec> {
+        Self::presets()
+            .into_iter()
+            .find(|p| p.name.as_deref().is_some_and(|n| n.eq_ignore_ascii_case(name)))
+    }
+
+    /// 常用 CRC 预置参数
+    pub fn presets() -> Vec<CrcSpec> {
+        vec![
+            Self::named("CRC-8/SMBUS", 8, 0x07, 0x00, false, false, 0x00, 0xF4),
+            Self::named("CRC-8/MAXIM-DOW", 8, 0x31, 0x00, true, true, 0x00, 0xA1),
+            Self::named("CRC-8/ROHC", 8, 0x07, 0xFF, true, true, 0x00, 0xD0),
+            Self::named("CRC-8/CDMA2000", 8, 0x9B, 0xFF, false, false, 0x00, 0xDA),
+            Self::named("CRC-8/AUTOSAR", 8, 0x2F, 0xFF, false, false, 0xFF, 0xDF),
+            Self::named("CRC-8/I-432-1", 8, 0x07, 0x00, false, false, 0x55, 0xA1),
+            Self::named("CRC-16/ARC", 16, 0x8005, 0x0000, true, true, 0x0000, 0xBB3D),
+            Self::named("CRC-16/MODBUS", 16, 0x8005, 0xFFFF, true, true, 0x0000, 0x4B37),
+            Self::named("CRC-16/USB", 16, 0x8005, 0xFFFF, true, true, 0xFFFF, 0xB4C8),
+            Self::named("CRC-16/MAXIM-DOW", 16, 0x8005, 0x0000, true, true, 0xFFFF, 0x44C2),
+            Self::named("CRC-16/IBM-3740", 16, 0x1021, 0xFFFF, false, false, 0x0000, 0x29B1),
+            Self::named("CRC-16/CCITT-FALSE", 16, 0x1021, 0xFFFF, false, false, 0x0000, 0x29B1),
+            Self::named("CRC-16/XMODEM", 16, 0x1021, 0x0000, false, false, 0x0000, 0x31C3),
+            Self::named("CRC-16/KERMIT", 16, 0x1021, 0x0000, true, true, 0x0000, 0x2189),
+            Self::named("CRC-16/X-25", 16, 0x1021, 0xFFFF, true, true, 0xFFFF, 0x906E),
+            Self::named("CRC-16/GENIBUS", 16, 0x1021, 0xFFFF, false, false, 0xFFFF, 0xD64E),
+            Self::named("CRC-16/DNP", 16, 0x3D65, 0x0000, true, true, 0xFFFF, 0xEA82),
+            Self::named("CRC-24/OPENPGP", 24, 0x86_4CFB, 0xB7_04CE, false, false, 0x00_0000, 0x21_CF02),
+            Self::named("CRC-32/ISO-HDLC", 32, 0x04C1_1DB7, 0xFFFF_FFFF, true, true, 0xFFFF_FFFF, 0xCBF4_3926),
+            Self::named("CRC-32/ISCSI", 32, 0x1EDC_6F41, 0xFFFF_FFFF, true, true, 0xFFFF_FFFF, 0xE306_9283),
+            Self::named("CRC-32/BZIP2", 32, 0x04C1_1DB7, 0xFFFF_FFFF, false, false, 0xFFFF_FFFF, 0xFC89_1918),
+            Self::named("CRC-32/MPEG-2", 32, 0x04C1_1DB7, 0xFFFF_FFFF, false, false, 0x0000_0000, 0x0376_E6E7),
+            Self::named("CRC-32/JAMCRC", 32, 0x04C1_1DB7, 0xFFFF_FFFF, true, true, 0x0000_0000, 0x340B_C6D9),
+            Self::named("CRC-32/CKSUM", 32, 0x04C1_1DB7, 0x0000_0000, false, false, 0xFFFF_FFFF, 0x765E_7680),
+            Self::named("CRC-40/GSM", 40, 0x00_0482_0009, 0x00_0000_0000, false, false, 0xFF_FFFF_FFFF, 0xD4_164F_C646),
+            Self::named("CRC-64/ECMA-182", 64, 0x42F0_E1EB_A9EA_3693, 0, false, false, 0, 0x6C40_DF5F_0B49_7347),
+            Self::named("CRC-64/XZ", 64, 0x42F0_E1EB_A9EA_3693, u64::MAX, true, true, u64::MAX, 0x995D_C9BB_DF19_39FA),
+            Self::named("CRC-64/GO-ISO", 64, 0x0000_0000_0000_001B, u64::MAX, true, true, u64::MAX, 0xB909_56C7_75A4_1001),
+        ]
+    }
+}
+
+/// 以 "0x..." 十六进制字符串序列化 u64，反序列化同时接受数字和字符串
+pub(crate) mod hex_u64 {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr {
+        Number(u64),
+        Text(String),
+    }
+
+    pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("0x{:X}", value))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        match Repr::deserialize(deserializer)? {
+            Repr::Number(n) => Ok(n),
+            Repr::Text(s) => parse(&s).map_err(serde::de::Error::custom),
+        }
+    }
+
+    pub(crate) fn parse(text: &str) -> Result<u64, String> {
+        let text = text.trim();
+        let digits = text.strip_prefix("0x")
+            .or_else(|| text.strip_prefix("0X"));
+        match digits {
+            Some(hex) => u64::from_str_radix(hex, 16),
+            None => text.parse::<u64>(),
+        }
+        .map_err(|e| format!("无效的数值 {}: {}", text, e))
+    }
+}
+
+/// `hex_u64` 的可选版本
+pub(crate) mod hex_u64_opt {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr {
+        Number(u64),
+        Text(String),
+    }
+
+    pub fn serialize<S: Serializer>(value: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(v) => super::hex_u64::serialize(v, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+        match Option::<Repr>::deserialize(deserializer)? {
+            Some(Repr::Number(n)) => Ok(Some(n)),
+            Some(Repr::Text(s)) => super::hex_u64::parse(&s).map(Some).map_err(serde::de::Error::custom),
+            None => Ok(None),
+        }
+    }
+}
+
+/// `hex_u64` 的数组版本
+pub(crate) mod hex_u64_vec {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use serde::ser::SerializeSeq;
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr {
+        Number(u64),
+        Text(String),
+    }
+
+    pub fn serialize<S: Serializer>(values: &[u64], serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(values.len()))?;
+        for value in values {
+            seq.serialize_element(&format!("0x{:X}", value))?;
+        }
+        seq.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u64>, D::Error> {
+        Vec::<Repr>::deserialize(deserializer)?
+            .into_iter()
+            .map(|repr| match repr {
+                Repr::Number(n) => Ok(n),
+                Repr::Text(s) => super::hex_u64::parse(&s).map_err(serde::de::Error::custom),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_match_check_values() {
+        for spec in CrcSpec::presets() {
+            let check = spec.check.unwrap();
+            assert_eq!(spec.compute(b"123456789"), check, "{}", spec.name.as_deref().unwrap_or_default());
+            assert!(spec.validate().is_ok());
+        }
+    }
+
+    #[test]
+    fn preset_lookup_ignores_case() {
+        let spec = CrcSpec::preset("crc-16/modbus").unwrap();
+        assert_eq!(spec.compute(b"123456789"), 0x4B37);
+        assert!(CrcSpec::preset("CRC-16/UNKNOWN").is_none());
+    }
+
+    #[test]
+    fn validate_rejects_wrong_check_value() {
+        let mut spec = CrcSpec::new(16, 0x1021, 0xFFFF, false, false, 0x0000);
+        spec.check = Some(0x31C3);
+        assert!(spec.validate().unwrap_err().contains("0x29B1"));
+        assert!(CrcSpec::new(4, 0x3, 0, false, false, 0).validate().is_err());
+    }
+}
@@ -0,0 +1,694 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use super::checksum::{read_uint, write_uint};
+use super::encoding::{encode_ascii_hex, encode_bcd, f64_to_half, parse_hex_string, parse_integer, parse_timestamp};
+use super::parser::FieldCursor;
+use super::protocol::*;
+
+/// 编码结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncodedFrame {
+    /// 帧数据
+    pub data: Vec<u8>,
+    /// 十六进制文本，与命令数据格式相同，可直接保存为命令
+    pub hex: String,
+}
+
+impl EncodedFrame {
+    pub fn new(data: Vec<u8>) -> Self {
+        let hex = data.iter()
+            .map(|b| format!("{:02X}", b))
+            .collect::<Vec<_>>()
+            .join(" ");
+        Self { data, hex }
+    }
+}
+
+/// 按协议与字段值编码一帧
+///
+/// 依次写入帧头、字段、校验值与帧尾，字段按声明的偏移与字节序写入，未给出的字段填 0，
+/// 配置了成帧编码时最后转义并加上定界字节。
+/// 长度或个数引用的字段、帧长度字段未给出时按实际内容计算；配置了比例或偏移的数值字段
+/// 按工程值给出，带取值映射的字段也可直接给出名称。
+///
+/// 字段值：整数（含 BCD 与 ASCII 十六进制）与浮点数为数值（也可为 "0x.." 字符串），
+/// 标志位与布尔字段为布尔，时间戳为数值（按字段单位）或 RFC 3339 / "YYYY-MM-DD HH:MM:SS" 文本（UTC），
+/// 字符串字段为文本，字节与十六进制字段为十六进制文本或字节数组，子结构为对象，数组为列表。
+pub fn encode_frame(protocol: &Protocol, values: &Map<String, Value>) -> Result<Vec<u8>, String> {
+    if protocol.text.is_some() {
+        return Err("文本协议不支持编码".to_string());
+    }
+    if protocol.decoder.is_some() {
+        return Err("内置解码器协议不支持按字段编码".to_string());
+    }
+
+    let variant = select_variant(protocol, values)?;
+
+    let known: Vec<&ProtocolField> = protocol.fields.iter()
+        .chain(variant.into_iter().flat_map(|v| &v.fields))
+        .collect();
+    let inputs = Inputs::new(values, &known)?;
+
+    let header = protocol.header.clone().unwrap_or_default();
+    let header_len = header.len();
+    let mut writer = FrameWriter {
+        protocol,
+        buffer: header,
+        positions: HashMap::new(),
+    };
+
+    // 消息布局的字段接在公共字段之后编码，与解析时的平移规则一致
+    let mut ints: HashMap<&str, u64> = HashMap::new();
+    let mut cursor = FieldCursor::default();
+    writer.encode_fields(&protocol.fields, &inputs, header_len, &mut ints, 0, &mut cursor)?;
+    if let Some(variant) = variant {
+        writer.encode_fields(&variant.fields, &inputs, header_len, &mut ints, 0, &mut cursor)?;
+    }
+    writer.reserve(header_len + cursor.end);
+
+    let frame = writer.finish(values)?;
+    Ok(match protocol.framing {
+        Some(ref framing) => framing.encode(&frame),
+        None => frame,
+    })
+}
+
+/// 按判别字段的取值选择消息布局，判别字段未给出时按 0 选择
+fn select_variant<'a>(protocol: &'a Protocol, values: &Map<String, Value>) -> Result<Option<&'a ProtocolVariant>, String> {
+    let Some(ref name) = protocol.discriminator else { return Ok(None) };
+    let Some(field) = protocol.fields.iter().find(|f| &f.name == name) else { return Ok(None) };
+
+    let value = match values.get(name) {
+        Some(value) => integer_input(field, value)
+            .map(|raw| to_unsigned(raw, field))
+            .map_err(|e| format!("字段 {}: {}", name, e))?,
+        None => 0,
+    };
+    Ok(protocol.select_variant(value))
+}
+
+/// 字段列表的输入值
+struct Inputs<'v> {
+    values: &'v Map<String, Value>,
+    /// 未给出、由被引用处的内容推算出的长度与个数字段取值
+    implied: HashMap<String, u64>,
+}
+
+impl<'v> Inputs<'v> {
+    /// 检查字段名称并推算长度与个数字段的取值
+    fn new(values: &'v Map<String, Value>, fields: &[&ProtocolField]) -> Result<Self, String> {
+        if let Some(name) = values.keys().find(|name| !fields.iter().any(|f| &f.name == *name)) {
+            return Err(format!("未知字段: {}", name));
+        }
+
+        let mut implied = HashMap::new();
+        for field in fields {
+            let Some(value) = values.get(&field.name) else { continue };
+
+            let (reference, len) = match (&field.count_from, &field.length_from) {
+                (Some(count_from), _) => match value {
+                    Value::Array(items) => (count_from, items.len()),
+                    _ => continue,
+                },
+                (None, Some(length_from)) if field.field_type.size().is_none()
+                    && !field.field_type.is_encoded_integer()
+                    && !field.is_array() => {
+                    let bytes = bytes_input(field, value).map_err(|e| format!("字段 {}: {}", field.name, e))?;
+                    (length_from, bytes.len())
+                }
+                _ => continue,
+            };
+            if values.contains_key(&reference.field) {
+                continue;
+            }
+
+            let resolved = reference_value(reference, len).ok_or_else(|| {
+                format!("字段 {}: 无法由长度 {} 推算字段 {} 的取值", field.name, len, reference.field)
+            })?;
+            if implied.get(&reference.field).is_some_and(|v| *v != resolved) {
+                return Err(format!("字段 {} 的长度与其他引用该字段的内容不一致", reference.field));
+            }
+            implied.insert(reference.field.clone(), resolved);
+        }
+
+        Ok(Self { values, implied })
+    }
+
+    /// 获取字段输入值
+    fn get(&self, name: &str) -> Option<&'v Value> {
+        self.values.get(name)
+    }
+}
+
+/// 由长度或个数反推引用字段的取值：(长度 - adjust) / multiplier
+fn reference_value(reference: &LengthRef, len: usize) -> Option<u64> {
+    if reference.multiplier <= 0 {
+        return None;
+    }
+    let covered = (len as i64).checked_sub(reference.adjust)?;
+    if covered < 0 || covered % reference.multiplier != 0 {
+        return None;
+    }
+    Some((covered / reference.multiplier) as u64)
+}
+
+/// 帧数据写入
+struct FrameWriter<'a> {
+    protocol: &'a Protocol,
+    buffer: Vec<u8>,
+    /// 公共字段的实际起始偏移，用于回填帧长度字段
+    positions: HashMap<&'a str, usize>,
+}
+
+impl<'a> FrameWriter<'a> {
+    /// 编码字段列表，偏移相对 `base`
+    fn encode_fields(
+        &mut self,
+        defs: &'a [ProtocolField],
+        inputs: &Inputs,
+        base: usize,
+        ints: &mut HashMap<&'a str, u64>,
+        depth: usize,
+        cursor: &mut FieldCursor,
+    ) -> Result<(), String> {
+        for field_def in defs {
+            let offset = cursor.place(field_def.offset);
+            let input = inputs.get(&field_def.name);
+
+            let actual_len = if field_def.is_array() || field_def.field_type == FieldType::Struct {
+                self.encode_compound(field_def, input, base + offset, ints, depth)?
+            } else {
+                let input = input.cloned()
+                    .or_else(|| inputs.implied.get(&field_def.name).map(|v| Value::from(*v)));
+                if depth == 0 {
+                    self.positions.insert(field_def.name.as_str(), base + offset);
+                }
+                self.encode_primitive(field_def, &field_def.name, input.as_ref(), base + offset, ints)?
+            };
+
+            let nominal = self.protocol.nominal_size(field_def);
+            cursor.advance(field_def.offset, offset, nominal, actual_len);
+        }
+
+        Ok(())
+    }
+
+    /// 编码基本类型字段，返回实际长度
+    fn encode_primitive(
+        &mut self,
+        field_def: &'a ProtocolField,
+        name: &str,
+        input: Option<&Value>,
+        start: usize,
+        ints: &mut HashMap<&'a str, u64>,
+    ) -> Result<usize, String> {
+        let field_len = if field_def.is_terminated() {
+            // 长度含结束符
+            let text = input.map(|input| bytes_input(field_def, input))
+                .transpose()
+                .map_err(|e| format!("字段 {}: {}", name, e))?;
+            text.map(|text| text.len()).unwrap_or(0) + 1
+        } else {
+            field_length(field_def, ints).map_err(|e| format!("字段 {}: {}", name, e))?
+        };
+        self.reserve(start + field_len);
+
+        let encoded = match field_def.bits {
+            Some(ref bits) => self.encode_bits(field_def, bits, input, start, field_len),
+            None => encode_value(field_def, input, field_len),
+        };
+        let (bytes, integer) = encoded.map_err(|e| format!("字段 {}: {}", name, e))?;
+
+        self.buffer[start..start + field_len].copy_from_slice(&bytes);
+        if let Some(integer) = integer {
+            ints.insert(field_def.name.as_str(), integer);
+        }
+        Ok(field_len)
+    }
+
+    /// 将位字段写入已有的容器字节
+    fn encode_bits(
+        &self,
+        field_def: &ProtocolField,
+        bits: &BitRange,
+        input: Option<&Value>,
+        start: usize,
+        field_len: usize,
+    ) -> Result<(Vec<u8>, Option<u64>), String> {
+        if !field_def.field_type.is_integer() {
+            return Err("位字段须使用整数类型".to_string());
+        }
+        let value = match input {
+            Some(input) => u64::try_from(integer_input(field_def, input)?)
+                .map_err(|_| "位字段取值不能为负".to_string())?,
+            None => 0,
+        };
+        let container = read_uint(&self.buffer[start..start + field_len], &field_def.byte_order);
+        let container = bits.insert(container, value, field_len as u32 * 8)?;
+        Ok((write_uint(container, field_len, &field_def.byte_order), Some(value)))
+    }
+
+    /// 编码数组或子结构字段，返回实际长度
+    fn encode_compound(
+        &mut self,
+        field_def: &'a ProtocolField,
+        input: Option<&Value>,
+        start: usize,
+        ints: &mut HashMap<&'a str, u64>,
+        depth: usize,
+    ) -> Result<usize, String> {
+        let structure = match field_def.field_type {
+            FieldType::Struct => {
+                let struct_name = field_def.struct_name.as_deref().unwrap_or_default();
+                let structure = self.protocol.find_struct(struct_name)
+                    .ok_or_else(|| format!("字段 {}: 子结构不存在: {}", field_def.name, struct_name))?;
+                if depth >= MAX_STRUCT_DEPTH {
+                    return Err(format!("字段 {}: 子结构嵌套过深", field_def.name));
+                }
+                Some(structure)
+            }
+            _ => None,
+        };
+
+        let count = match field_def.count_from {
+            Some(ref count_from) => resolve_reference(count_from, ints)
+                .map_err(|e| format!("字段 {}: {}", field_def.name, e))?,
+            None => field_def.count.unwrap_or(1),
+        };
+
+        let elements: Vec<Option<&Value>> = if field_def.is_array() {
+            let items: &[Value] = match input {
+                Some(Value::Array(items)) => items.as_slice(),
+                Some(_) => return Err(format!("字段 {}: 须为列表", field_def.name)),
+                None => &[],
+            };
+            if items.len() > count {
+                return Err(format!("字段 {}: 给出 {} 项, 超出 {} 项", field_def.name, items.len(), count));
+            }
+            (0..count).map(|index| items.get(index)).collect()
+        } else {
+            vec![input]
+        };
+
+        let empty = Map::new();
+        let mut pos = start;
+        for (index, element) in elements.into_iter().enumerate() {
+            let element_name = if field_def.is_array() {
+                format!("{}[{}]", field_def.name, index)
+            } else {
+                field_def.name.clone()
+            };
+
+            let len = match structure {
+                Some(structure) => {
+                    let values = match element {
+                        Some(Value::Object(values)) => values,
+                        Some(_) => return Err(format!("字段 {}: 须为对象", element_name)),
+                        None => &empty,
+                    };
+                    let fields: Vec<&ProtocolField> = structure.fields.iter().collect();
+                    let inputs = Inputs::new(values, &fields)
+                        .map_err(|e| format!("字段 {}: {}", element_name, e))?;
+
+                    // 与解析一致：数组元素各自拥有字段值作用域
+                    let mut cursor = FieldCursor::default();
+                    let result = if field_def.is_array() {
+                        let mut scoped = ints.clone();
+                        self.encode_fields(&structure.fields, &inputs, pos, &mut scoped, depth + 1, &mut cursor)
+                    } else {
+                        self.encode_fields(&structure.fields, &inputs, pos, ints, depth + 1, &mut cursor)
+                    };
+                    result.map_err(|e| format!("字段 {}: {}", element_name, e))?;
+                    self.reserve(pos + cursor.end);
+                    cursor.end
+                }
+                None => self.encode_primitive(field_def, &element_name, element, pos, ints)?,
+            };
+
+            if len == 0 {
+                break;
+            }
+            pos += len;
+        }
+
+        Ok(pos - start)
+    }
+
+    /// 确保缓冲区至少有 `len` 字节，不足部分填 0
+    fn reserve(&mut self, len: usize) {
+        if self.buffer.len() < len {
+            self.buffer.resize(len, 0);
+        }
+    }
+
+    /// 预留校验值，回填帧长度字段，写入校验值与帧尾
+    fn finish(mut self, values: &Map<String, Value>) -> Result<Vec<u8>, String> {
+        let protocol = self.protocol;
+        let footer = protocol.footer.clone().unwrap_or_default();
+
+        let checksum = protocol.checksum.as_ref().filter(|c| **c != ChecksumType::None);
+        let layout = protocol.checksum_layout.clone().unwrap_or_default();
+        let checksum_at = checksum.map(|checksum| {
+            let position = layout.position.unwrap_or(self.buffer.len());
+            self.reserve(position + checksum.size());
+            position
+        });
+
+        if let Some(ref length_field) = protocol.length_field {
+            if !values.contains_key(&length_field.field) {
+                let total = self.buffer.len() + footer.len();
+                self.write_length(length_field, total)?;
+            }
+        }
+
+        if let (Some(checksum), Some(position)) = (checksum, checksum_at) {
+            let end = layout.end.unwrap_or(position);
+            if layout.start > end || end > self.buffer.len() {
+                return Err("校验范围超出帧长度".to_string());
+            }
+            let size = checksum.size();
+            let value = checksum.compute(&self.buffer[layout.start..end]);
+            self.buffer[position..position + size].copy_from_slice(&write_uint(value, size, &layout.byte_order));
+        }
+
+        self.buffer.extend_from_slice(&footer);
+        Ok(self.buffer)
+    }
+
+    /// 按帧总长回填帧长度字段
+    fn write_length(&mut self, length_field: &LengthField, total: usize) -> Result<(), String> {
+        let Some(field) = self.protocol.fields.iter().find(|f| f.name == length_field.field) else {
+            return Ok(());
+        };
+        let (Some(&start), Some(size)) = (self.positions.get(field.name.as_str()), field.field_type.size()) else {
+            return Ok(());
+        };
+
+        let covered = total.checked_sub(length_field.start + length_field.trailing)
+            .and_then(|covered| (covered as i64).checked_sub(length_field.adjust))
+            .filter(|covered| length_field.multiplier > 0 && *covered >= 0 && covered % length_field.multiplier == 0)
+            .ok_or_else(|| format!("字段 {}: 无法由帧长度 {} 推算长度值", field.name, total))?;
+        let value = (covered / length_field.multiplier) as u64;
+        if size < 8 && value >> (size * 8) != 0 {
+            return Err(format!("字段 {}: 长度值 {} 超出字段范围", field.name, value));
+        }
+
+        self.buffer[start..start + size].copy_from_slice(&write_uint(value, size, &field.byte_order));
+        Ok(())
+    }
+}
+
+/// 计算字段实际长度，变长字段从已编码的字段值中取长度
+fn field_length(field_def: &ProtocolField, ints: &HashMap<&str, u64>) -> Result<usize, String> {
+    if let Some(size) = field_def.field_type.size() {
+        return Ok(size);
+    }
+    match field_def.length_from {
+        Some(ref length_from) => resolve_reference(length_from, ints),
+        None => Ok(field_def.length.unwrap_or(1)),
+    }
+}
+
+/// 从已编码的字段值计算长度或个数
+fn resolve_reference(reference: &LengthRef, ints: &HashMap<&str, u64>) -> Result<usize, String> {
+    let value = ints.get(reference.field.as_str())
+        .ok_or_else(|| format!("引用的字段不存在: {}", reference.field))?;
+    reference.resolve(*value).ok_or_else(|| format!("长度无效: {}", value))
+}
+
+/// 编码字段值，返回字节及整数原始值（供长度引用使用）
+fn encode_value(field_def: &ProtocolField, input: Option<&Value>, field_len: usize) -> Result<(Vec<u8>, Option<u64>), String> {
+    let order = &field_def.byte_order;
+    match field_def.field_type {
+        _ if field_def.field_type.is_integer() => {
+            let raw = match input {
+                Some(input) => integer_input(field_def, input)?,
+                None => 0,
+            };
+            check_range(raw, &field_def.field_type)?;
+            let value = to_unsigned(raw, field_def);
+            Ok((write_uint(value, field_len, order), Some(value)))
+        }
+        FieldType::Bcd | FieldType::AsciiHex => {
+            let raw = match input {
+                Some(input) => integer_input(field_def, input)?,
+                None => 0,
+            };
+            let value = u64::try_from(raw).map_err(|_| format!("取值 {} 超出范围", raw))?;
+            let bytes = match field_def.field_type {
+                FieldType::Bcd => encode_bcd(value, field_len, order)?,
+                _ => encode_ascii_hex(value, field_len)?,
+            };
+            Ok((bytes, Some(value)))
+        }
+        FieldType::Float16 | FieldType::Float32 | FieldType::Float64 => {
+            let value = match input {
+                Some(input) => float_input(field_def, input)?,
+                None => 0.0,
+            };
+            let bits = match field_def.field_type {
+                FieldType::Float16 => f64_to_half(value) as u64,
+                FieldType::Float32 => (value as f32).to_bits() as u64,
+                _ => value.to_bits(),
+            };
+            Ok((write_uint(bits, field_len, order), None))
+        }
+        FieldType::Bool => {
+            let value = match input {
+                Some(input) => integer_input(field_def, input)? != 0,
+                None => false,
+            };
+            Ok((vec![value as u8], None))
+        }
+        FieldType::Timestamp | FieldType::TimestampMs => {
+            let millis = match input {
+                Some(input) => timestamp_input(field_def, input)?,
+                None => 0,
+            };
+            let raw = match field_def.field_type {
+                FieldType::Timestamp => u32::try_from(millis.div_euclid(1000))
+                    .map_err(|_| "时间超出 4 字节秒级时间戳范围".to_string())? as u64,
+                _ => millis as u64,
+            };
+            Ok((write_uint(raw, field_len, order), None))
+        }
+        _ => {
+            let mut bytes = match input {
+                Some(input) => bytes_input(field_def, input)?,
+                None => Vec::new(),
+            };
+            if field_def.field_type == FieldType::CString && bytes.contains(&0) {
+                return Err("字符串不能包含 0x00".to_string());
+            }
+            if bytes.len() > field_len {
+                return Err(format!("内容 {} 字节, 超出字段长度 {} 字节", bytes.len(), field_len));
+            }
+            bytes.resize(field_len, 0);
+            Ok((bytes, None))
+        }
+    }
+}
+
+/// 读取整数输入，取值映射名称直接对应原始值，数值按比例与偏移还原
+fn integer_input(field_def: &ProtocolField, input: &Value) -> Result<i128, String> {
+    match input {
+        Value::Bool(flag) => Ok(*flag as i128),
+        Value::Number(number) => {
+            if field_def.scale.is_some() || field_def.value_offset.is_some() {
+                let value = number.as_f64().ok_or("无效的数值")?;
+                let raw = field_def.unapply_scaling(value).ok_or("比例系数为 0, 无法还原原始值")?;
+                return Ok(raw.round() as i128);
+            }
+            if let Some(value) = number.as_u64() {
+                return Ok(value as i128);
+            }
+            if let Some(value) = number.as_i64() {
+                return Ok(value as i128);
+            }
+            match number.as_f64() {
+                Some(value) if value.fract() == 0.0 => Ok(value as i128),
+                _ => Err(format!("须为整数: {}", number)),
+            }
+        }
+        Value::String(text) => {
+            let label = field_def.value_map.as_ref()
+                .and_then(|map| map.entries.iter().find(|e| e.label == *text));
+            if let Some(entry) = label {
+                return Ok(entry.value as i128);
+            }
+            parse_integer(text).ok_or_else(|| format!("无法识别的取值: {}", text))
+        }
+        _ => Err("须为数值".to_string()),
+    }
+}
+
+/// 检查整数是否在字段类型的取值范围内
+fn check_range(value: i128, field_type: &FieldType) -> Result<(), String> {
+    let bits = field_type.size().unwrap_or(8) as u32 * 8;
+    let (min, max) = match field_type {
+        FieldType::Int8 | FieldType::Int16 | FieldType::Int32 | FieldType::Int64 => {
+            (-(1i128 << (bits - 1)), (1i128 << (bits - 1)) - 1)
+        }
+        _ => (0, (1i128 << bits) - 1),
+    };
+    if value < min || value > max {
+        return Err(format!("取值 {} 超出范围 {}..={}", value, min, max));
+    }
+    Ok(())
+}
+
+/// 整数按字段宽度转为无符号原始值（负数取补码）
+fn to_unsigned(value: i128, field_def: &ProtocolField) -> u64 {
+    let bits = field_def.field_type.size().unwrap_or(8) * 8;
+    let value = value as i64 as u64;
+    if bits >= 64 { value } else { value & ((1u64 << bits) - 1) }
+}
+
+/// 读取浮点数输入，按比例与偏移还原
+fn float_input(field_def: &ProtocolField, input: &Value) -> Result<f64, String> {
+    let value = match input {
+        Value::Number(number) => number.as_f64().ok_or("无效的数值")?,
+        Value::String(text) => text.trim().parse().map_err(|_| format!("无法识别的取值: {}", text))?,
+        _ => return Err("须为数值".to_string()),
+    };
+    field_def.unapply_scaling(value).ok_or_else(|| "比例系数为 0, 无法还原原始值".to_string())
+}
+
+/// 读取时间戳输入，返回 Unix 时间戳（毫秒）；数值按字段单位（秒或毫秒）给出
+fn timestamp_input(field_def: &ProtocolField, input: &Value) -> Result<i64, String> {
+    let unit = match field_def.field_type {
+        FieldType::Timestamp => 1000,
+        _ => 1,
+    };
+    let value = match input {
+        Value::Number(number) => number.as_i64().ok_or_else(|| format!("须为整数: {}", number))?,
+        Value::String(text) => match parse_timestamp(text) {
+            Some(millis) => return Ok(millis),
+            None => parse_integer(text)
+                .and_then(|v| i64::try_from(v).ok())
+                .ok_or_else(|| format!("无法识别的时间: {}", text))?,
+        },
+        _ => return Err("须为数值或时间文本".to_string()),
+    };
+    value.checked_mul(unit).ok_or_else(|| format!("时间超出范围: {}", value))
+}
+
+/// 读取字符串或字节输入
+fn bytes_input(field_def: &ProtocolField, input: &Value) -> Result<Vec<u8>, String> {
+    match input {
+        Value::String(text) if matches!(field_def.field_type, FieldType::String | FieldType::CString) => {
+            Ok(text.as_bytes().to_vec())
+        }
+        Value::String(text) => parse_hex_string(text),
+        Value::Array(items) => items.iter()
+            .map(|item| {
+                item.as_u64()
+                    .and_then(|b| u8::try_from(b).ok())
+                    .ok_or_else(|| format!("无效的字节: {}", item))
+            })
+            .collect(),
+        _ => Err("须为文本或字节列表".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::ProtocolParser;
+    use serde_json::json;
+
+    fn encode(protocol: &Protocol, values: Value) -> Result<Vec<u8>, String> {
+        encode_frame(protocol, values.as_object().unwrap())
+    }
+
+    fn field<'f>(frame: &'f ParsedFrame, name: &str) -> &'f ParsedField {
+        frame.fields.iter().find(|f| f.name == name).unwrap()
+    }
+
+    fn sample_protocol() -> Protocol {
+        let mut protocol = Protocol::new("示例").with_header(vec![0xAA, 0x55]).with_footer(vec![0x0D]);
+        protocol.fields = vec![
+            ProtocolField::new("len", FieldType::Uint8, 0),
+            ProtocolField::new("cmd", FieldType::Uint8, 1).with_value_map(ValueMap {
+                entries: vec![ValueLabel { value: 6, label: "ACK".to_string() }],
+                fallback: None,
+            }),
+            ProtocolField::new("flag", FieldType::Uint8, 2)
+                .with_bits(BitRange { offset: 0, width: 1, order: BitOrder::LsbFirst }),
+            ProtocolField::new("mode", FieldType::Uint8, 2)
+                .with_bits(BitRange { offset: 4, width: 4, order: BitOrder::LsbFirst }),
+            ProtocolField::new("temp", FieldType::Int16, 3)
+                .with_scaling(0.1, 0.0, "C")
+                .with_byte_order(ByteOrder::LittleEndian),
+            ProtocolField::new("n", FieldType::Uint8, 5),
+            ProtocolField::new("data", FieldType::Bytes, 6)
+                .with_length_from(LengthRef { field: "n".to_string(), adjust: 0, multiplier: 1 }),
+        ];
+        protocol.checksum = Some(ChecksumType::Crc16);
+        protocol.length_field = Some(LengthField {
+            field: "len".to_string(),
+            adjust: 0,
+            multiplier: 1,
+            start: 0,
+            trailing: 0,
+        });
+        protocol
+    }
+
+    #[test]
+    fn encoded_frame_parses_back_to_inputs() {
+        let protocol = sample_protocol();
+        let bytes = encode(&protocol, json!({
+            "cmd": "ACK",
+            "flag": true,
+            "mode": 5,
+            "temp": -12.3,
+            "data": "01 02 03",
+        })).unwrap();
+
+        assert_eq!(&bytes[..2], [0xAA, 0x55]);
+        assert_eq!(bytes.last(), Some(&0x0D));
+
+        let frame = ProtocolParser::new().parse_with_protocol(&bytes, &protocol);
+        assert!(frame.valid, "{:?}", frame.error);
+        assert_eq!(usize::from(bytes[2]), bytes.len());
+        assert!(matches!(field(&frame, "cmd").value, FieldValue::Enum { value: 6, .. }));
+        assert!(matches!(field(&frame, "flag").value, FieldValue::Bool(true)));
+        assert_eq!(field(&frame, "mode").raw_value, Some(5.0));
+        assert_eq!(field(&frame, "temp").raw_value, Some(-123.0));
+        assert!(matches!(field(&frame, "n").value, FieldValue::Unsigned(3)));
+        assert_eq!(field(&frame, "data").raw_bytes, [0x01, 0x02, 0x03]);
+    }
+
+    #[test]
+    fn variant_fields_follow_discriminator() {
+        let mut protocol = Protocol::new("布局");
+        protocol.fields = vec![ProtocolField::new("cmd", FieldType::Uint8, 0)];
+        protocol.discriminator = Some("cmd".to_string());
+        protocol.variants = vec![ProtocolVariant {
+            name: "读".to_string(),
+            values: vec![0x03],
+            fields: vec![ProtocolField::new("addr", FieldType::Uint16, 1)],
+        }];
+
+        let bytes = encode(&protocol, json!({ "cmd": 3, "addr": "0x1234" })).unwrap();
+        assert_eq!(bytes, [0x03, 0x12, 0x34]);
+
+        let frame = ProtocolParser::new().parse_with_protocol(&bytes, &protocol);
+        assert!(frame.valid, "{:?}", frame.error);
+        assert!(matches!(field(&frame, "addr").value, FieldValue::Unsigned(0x1234)));
+
+        // 布局之外的字段不可编码
+        assert!(encode(&protocol, json!({ "cmd": 1, "addr": 1 })).is_err());
+    }
+
+    #[test]
+    fn invalid_inputs_are_rejected() {
+        let protocol = sample_protocol();
+        assert!(encode(&protocol, json!({ "bogus": 1 })).unwrap_err().contains("未知字段"));
+        assert!(encode(&protocol, json!({ "cmd": 300 })).is_err());
+        assert!(encode(&protocol, json!({ "mode": 16 })).is_err());
+    }
+}
@@ -0,0 +1,209 @@
+use super::protocol::ByteOrder;
+
+/// 半精度浮点数转为双精度
+pub(crate) fn half_to_f64(bits: u16) -> f64 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = ((bits >> 10) & 0x1F) as i32;
+    let fraction = (bits & 0x03FF) as f64;
+
+    let magnitude = match exponent {
+        0 => fraction * 2f64.powi(-24),
+        0x1F if fraction == 0.0 => f64::INFINITY,
+        0x1F => f64::NAN,
+        _ => (1.0 + fraction / 1024.0) * 2f64.powi(exponent - 15),
+    };
+    sign * magnitude
+}
+
+/// 双精度浮点数转为半精度，超出范围时为无穷大，尾数四舍五入
+pub(crate) fn f64_to_half(value: f64) -> u16 {
+    let bits = (value as f32).to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xFF) as i32;
+    let mantissa = bits & 0x007F_FFFF;
+
+    if exponent == 0xFF {
+        return sign | 0x7C00 | if mantissa != 0 { 0x0200 } else { 0 };
+    }
+
+    let half_exponent = exponent - 127 + 15;
+    if half_exponent >= 0x1F {
+        return sign | 0x7C00;
+    }
+    if half_exponent <= 0 {
+        // 非规格化数
+        if half_exponent < -10 {
+            return sign;
+        }
+        let mantissa = mantissa | 0x0080_0000;
+        let shift = (14 - half_exponent) as u32;
+        let rounded = (mantissa >> shift) + ((mantissa >> (shift - 1)) & 1);
+        return sign | rounded as u16;
+    }
+
+    // 舍入进位可直接进入指数位
+    let half = ((half_exponent as u32) << 10) | (mantissa >> 13);
+    sign | (half + ((mantissa >> 12) & 1)) as u16
+}
+
+/// 解码压缩 BCD，小端序时低位数字在前
+pub(crate) fn decode_bcd(bytes: &[u8], byte_order: &ByteOrder) -> Result<u64, String> {
+    let mut ordered = bytes.to_vec();
+    if *byte_order == ByteOrder::LittleEndian {
+        ordered.reverse();
+    }
+
+    ordered.iter()
+        .flat_map(|b| [b >> 4, b & 0x0F])
+        .try_fold(0u64, |acc, digit| {
+            if digit > 9 {
+                return Err(format!("无效的 BCD 数字: 0x{:X}", digit));
+            }
+            acc.checked_mul(10)
+                .and_then(|acc| acc.checked_add(digit as u64))
+                .ok_or_else(|| "BCD 数值超出范围".to_string())
+        })
+}
+
+/// 编码压缩 BCD，位数不足时高位补 0
+pub(crate) fn encode_bcd(value: u64, len: usize, byte_order: &ByteOrder) -> Result<Vec<u8>, String> {
+    let digits = value.to_string();
+    if digits.len() > len * 2 {
+        return Err(format!("取值 {} 超出 {} 位 BCD", value, len * 2));
+    }
+
+    let padded = format!("{:0>width$}", digits, width = len * 2);
+    let mut bytes: Vec<u8> = padded.as_bytes()
+        .chunks(2)
+        .map(|pair| ((pair[0] - b'0') << 4) | (pair[1] - b'0'))
+        .collect();
+    if *byte_order == ByteOrder::LittleEndian {
+        bytes.reverse();
+    }
+    Ok(bytes)
+}
+
+/// 解码 ASCII 十六进制数字
+pub(crate) fn decode_ascii_hex(bytes: &[u8]) -> Result<u64, String> {
+    if bytes.is_empty() || bytes.len() > 16 {
+        return Err(format!("ASCII 十六进制长度须为 1..=16, 实际 {}", bytes.len()));
+    }
+    let text = std::str::from_utf8(bytes).map_err(|_| "包含非 ASCII 字符".to_string())?;
+    u64::from_str_radix(text, 16).map_err(|_| format!("无效的十六进制数字: {}", text))
+}
+
+/// 编码 ASCII 十六进制数字（大写），位数不足时高位补 0
+pub(crate) fn encode_ascii_hex(value: u64, len: usize) -> Result<Vec<u8>, String> {
+    let text = format!("{:0width$X}", value, width = len);
+    if text.len() > len {
+        return Err(format!("取值 0x{:X} 超出 {} 位十六进制", value, len));
+    }
+    Ok(text.into_bytes())
+}
+
+/// 解析十六进制字符串为字节数组
+pub fn parse_hex_string(hex: &str) -> Result<Vec<u8>, String> {
+    let clean_hex: String = hex.chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+
+    if !clean_hex.len().is_multiple_of(2) {
+        return Err("十六进制字符串长度必须为偶数".to_string());
+    }
+
+    (0..clean_hex.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&clean_hex[i..i + 2], 16)
+                .map_err(|e| format!("无效的十六进制字符: {}", e))
+        })
+        .collect()
+}
+
+/// 解析十进制或 "0x" 前缀的十六进制整数
+pub(crate) fn parse_integer(text: &str) -> Option<i128> {
+    let text = text.trim();
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => i128::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+/// 格式化 Unix 时间戳（毫秒），显示为 UTC 时间
+pub(crate) fn format_timestamp(millis: i64, with_millis: bool) -> String {
+    let format = if with_millis { "%Y-%m-%d %H:%M:%S%.3f UTC" } else { "%Y-%m-%d %H:%M:%S UTC" };
+    match chrono::DateTime::from_timestamp_millis(millis) {
+        Some(time) => time.format(format).to_string(),
+        None => millis.to_string(),
+    }
+}
+
+/// 解析时间文本为 Unix 时间戳（毫秒），支持 RFC 3339 及 "YYYY-MM-DD HH:MM:SS[.fff]"（UTC）
+pub(crate) fn parse_timestamp(text: &str) -> Option<i64> {
+    let text = text.trim();
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(text) {
+        return Some(time.timestamp_millis());
+    }
+    let text = text.trim_end_matches("UTC").trim_end();
+    chrono::NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f")
+        .ok()
+        .map(|time| time.and_utc().timestamp_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn half_precision_conversion() {
+        let vectors = [
+            (0.0, 0x0000),
+            (1.0, 0x3C00),
+            (-2.0, 0xC000),
+            (0.1, 0x2E66),
+            (65504.0, 0x7BFF),
+            (1e6, 0x7C00),
+            (6e-8, 0x0001),
+            (3.0e-5, 0x01F7),
+        ];
+        for (value, bits) in vectors {
+            assert_eq!(f64_to_half(value), bits, "{}", value);
+        }
+
+        assert_eq!(half_to_f64(0x3C00), 1.0);
+        assert_eq!(half_to_f64(0xC000), -2.0);
+        assert_eq!(half_to_f64(0x7BFF), 65504.0);
+        assert_eq!(half_to_f64(0x0001), 2f64.powi(-24));
+        assert_eq!(half_to_f64(0xFC00), f64::NEG_INFINITY);
+        assert!(half_to_f64(0x7E00).is_nan());
+        assert!(half_to_f64(f64_to_half(f64::NAN)).is_nan());
+        assert!((half_to_f64(0x2E66) - 0.1).abs() < 1e-4);
+    }
+
+    #[test]
+    fn bcd_round_trip() {
+        assert_eq!(encode_bcd(1234, 2, &ByteOrder::BigEndian).unwrap(), [0x12, 0x34]);
+        assert_eq!(encode_bcd(1234, 3, &ByteOrder::LittleEndian).unwrap(), [0x34, 0x12, 0x00]);
+        assert_eq!(decode_bcd(&[0x12, 0x34], &ByteOrder::BigEndian).unwrap(), 1234);
+        assert_eq!(decode_bcd(&[0x34, 0x12, 0x00], &ByteOrder::LittleEndian).unwrap(), 1234);
+
+        for value in [0, 7, 99, 123_456, 9_999_999_999_999_999] {
+            let bytes = encode_bcd(value, 8, &ByteOrder::BigEndian).unwrap();
+            assert_eq!(decode_bcd(&bytes, &ByteOrder::BigEndian).unwrap(), value);
+        }
+
+        assert!(encode_bcd(100, 1, &ByteOrder::BigEndian).is_err());
+        assert!(decode_bcd(&[0x1A], &ByteOrder::BigEndian).is_err());
+        assert!(decode_bcd(&[0x99; 10], &ByteOrder::BigEndian).is_err());
+    }
+
+    #[test]
+    fn ascii_hex_round_trip() {
+        assert_eq!(encode_ascii_hex(0xAB, 4).unwrap(), b"00AB");
+        assert_eq!(decode_ascii_hex(b"00ab").unwrap(), 0xAB);
+        assert_eq!(decode_ascii_hex(&encode_ascii_hex(u64::MAX, 16).unwrap()).unwrap(), u64::MAX);
+        assert!(encode_ascii_hex(0x100, 2).is_err());
+        assert!(decode_ascii_hex(b"").is_err());
+        assert!(decode_ascii_hex(b"0G").is_err());
+    }
+}
@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+use super::protocol::*;
+use super::validate::check_protocol;
+
+/// 协议文件格式标识
+pub const PROTOCOL_FILE_FORMAT: &str = "hypercom.protocols";
+
+/// 协议文件格式版本
+pub const PROTOCOL_FILE_VERSION: u32 = 1;
+
+/// 协议交换文件，格式说明见 plans/protocol-format.md
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolFile {
+    /// 格式标识，固定为 "hypercom.protocols"
+    pub format: String,
+    /// 格式版本
+    pub version: u32,
+    /// 导出时间
+    #[serde(default)]
+    pub exported_at: i64,
+    /// 协议列表
+    pub protocols: Vec<Protocol>,
+}
+
+/// 导入冲突处理方式
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportConflict {
+    /// 覆盖已有协议（保留已有协议的 ID）
+    #[default]
+    Overwrite,
+    /// 跳过冲突的协议
+    Skip,
+    /// 以新 ID 和新名称并存
+    KeepBoth,
+}
+
+/// 导入结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    /// 新增的协议名称
+    pub added: Vec<String>,
+    /// 覆盖的协议名称
+    pub replaced: Vec<String>,
+    /// 跳过的协议名称
+    pub skipped: Vec<String>,
+    /// 已导入但未能保存到数据库的协议及原因
+    pub warnings: Vec<String>,
+    /// 因库中已有更新的版本而未导入的协议及原因
+    pub failed: Vec<String>,
+}
+
+/// 序列化协议文件
+pub fn write_protocol_file(protocols: Vec<Protocol>) -> Result<String, String> {
+    let file = ProtocolFile {
+        format: PROTOCOL_FILE_FORMAT.to_string(),
+        version: PROTOCOL_FILE_VERSION,
+        exported_at: chrono::Utc::now().timestamp_millis(),
+        protocols,
+    };
+    serde_json::to_string_pretty(&file)
+        .map_err(|e| format!("序列化协议文件失败: {}", e))
+}
+
+/// 解析并校验协议文件，任一协议不合法时整体失败
+pub fn read_protocol_file(content: &str) -> Result<Vec<Protocol>, String> {
+    let value: serde_json::Value = serde_json::from_str(content)
+        .map_err(|e| format!("解析协议文件失败: {}", e))?;
+
+    let format = value.get("format").and_then(|v| v.as_str());
+    if format != Some(PROTOCOL_FILE_FORMAT) {
+        return Err(format!("不是协议文件: format 须为 \"{}\"", PROTOCOL_FILE_FORMAT));
+    }
+
+    let version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+    if version == 0 || version > PROTOCOL_FILE_VERSION as u64 {
+        return Err(format!("不支持的协议文件版本: {}", version));
+    }
+
+    let items = value.get("protocols")
+        .and_then(|v| v.as_array())
+        .ok_or("协议文件缺少 protocols 列表")?;
+
+    items.iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let protocol = decode_protocol(item)
+                .map_err(|e| format!("协议 #{}: {}", index + 1, e))?;
+            check_protocol(&protocol)
+                .map_err(|e| format!("协议 {}: {}", protocol.name, e))?;
+            Ok(protocol)
+        })
+        .collect()
+}
+
+/// 反序列化单个协议，失败时定位到出错的字段
+fn decode_protocol(item: &serde_json::Value) -> Result<Protocol, String> {
+    serde_json::from_value::<Protocol>(item.clone()).map_err(|e| {
+        let fields = item.get("fields").and_then(|v| v.as_array());
+        let field_error = fields.into_iter().flatten().enumerate().find_map(|(index, field)| {
+            serde_json::from_value::<ProtocolField>(field.clone()).err().map(|e| {
+                let name = field.get("name").and_then(|v| v.as_str())
+                    .map(|name| name.to_string())
+                    .unwrap_or_else(|| format!("#{}", index + 1));
+                format!("字段 {}: {}", name, e)
+            })
+        });
+        field_error.unwrap_or_else(|| e.to_string())
+    })
+}
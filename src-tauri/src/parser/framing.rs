@@ -0,0 +1,230 @@
+use serde::{Deserialize, Serialize};
+
+/// SLIP 结束字节
+const SLIP_END: u8 = 0xC0;
+/// SLIP 转义字节
+const SLIP_ESC: u8 = 0xDB;
+/// 转义后的结束字节
+const SLIP_ESC_END: u8 = 0xDC;
+/// 转义后的转义字节
+const SLIP_ESC_ESC: u8 = 0xDD;
+
+/// 成帧编码
+///
+/// 线路上的帧由定界字节分隔，帧内与定界字节冲突的数据经过转义。
+/// 解析前先解除转义，帧头、字段与校验值均作用于还原后的数据；编码时最后进行转义。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Framing {
+    /// SLIP（RFC 1055）：以 0xC0 结束，0xDB 转义
+    Slip,
+    /// HDLC 异步成帧（RFC 1662）：标志字节包围，转义字节后跟原字节异或值
+    Hdlc(HdlcParams),
+    /// COBS：消除数据中的 0x00，以 0x00 结束
+    Cobs,
+}
+
+/// HDLC 成帧参数
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HdlcParams {
+    /// 标志字节
+    pub flag: u8,
+    /// 转义字节
+    pub escape: u8,
+    /// 转义时的异或值
+    pub xor: u8,
+}
+
+impl Default for HdlcParams {
+    fn default() -> Self {
+        Self {
+            flag: 0x7E,
+            escape: 0x7D,
+            xor: 0x20,
+        }
+    }
+}
+
+impl HdlcParams {
+    /// 检查参数，转义后的字节不能再是标志或转义字节
+    pub fn validate(&self) -> Result<(), String> {
+        if self.flag == self.escape {
+            return Err("标志字节与转义字节不能相同".to_string());
+        }
+        for byte in [self.flag, self.escape] {
+            let escaped = byte ^ self.xor;
+            if escaped == self.flag || escaped == self.escape {
+                return Err(format!("异或值 0x{:02X} 无法转义 0x{:02X}", self.xor, byte));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Framing {
+    /// 帧定界字节
+    pub fn delimiter(&self) -> u8 {
+        match self {
+            Framing::Slip => SLIP_END,
+            Framing::Hdlc(params) => params.flag,
+            Framing::Cobs => 0x00,
+        }
+    }
+
+    /// 转义数据并加上定界字节
+    pub fn encode(&self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Framing::Slip => {
+                let mut out = vec![SLIP_END];
+                for &byte in data {
+                    match byte {
+                        SLIP_END => out.extend([SLIP_ESC, SLIP_ESC_END]),
+                        SLIP_ESC => out.extend([SLIP_ESC, SLIP_ESC_ESC]),
+                        _ => out.push(byte),
+                    }
+                }
+                out.push(SLIP_END);
+                out
+            }
+            Framing::Hdlc(params) => {
+                let mut out = vec![params.flag];
+                for &byte in data {
+                    if byte == params.flag || byte == params.escape {
+                        out.extend([params.escape, byte ^ params.xor]);
+                    } else {
+                        out.push(byte);
+                    }
+                }
+                out.push(params.flag);
+                out
+            }
+            Framing::Cobs => cobs_encode(data),
+        }
+    }
+
+    /// 去除首尾定界字节并解除转义，格式错误时返回错误信息
+    pub fn decode(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        let delimiter = self.delimiter();
+        let start = data.iter().take_while(|b| **b == delimiter).count();
+        let end = data.len() - data[start..].iter().rev().take_while(|b| **b == delimiter).count();
+        let data = &data[start..end];
+        if data.contains(&delimiter) {
+            return Err(format!("帧内出现定界字节 0x{:02X}", delimiter));
+        }
+
+        match self {
+            Framing::Slip => unescape(data, SLIP_ESC, |byte| match byte {
+                SLIP_ESC_END => Some(SLIP_END),
+                SLIP_ESC_ESC => Some(SLIP_ESC),
+                _ => None,
+            }),
+            // 与 PPP 兼容，转义字节之后的任意字节均按异或还原
+            Framing::Hdlc(params) => unescape(data, params.escape, |byte| Some(byte ^ params.xor)),
+            Framing::Cobs => cobs_decode(data),
+        }
+    }
+}
+
+/// 解除转义，`restore` 给出转义字节之后的字节对应的原字节
+fn unescape(data: &[u8], escape: u8, restore: impl Fn(u8) -> Option<u8>) -> Result<Vec<u8>, String> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut bytes = data.iter();
+    while let Some(&byte) = bytes.next() {
+        if byte != escape {
+            out.push(byte);
+            continue;
+        }
+        let next = bytes.next().ok_or("转义字节位于帧末尾")?;
+        let original = restore(*next).ok_or_else(|| format!("无效的转义序列: {:02X} {:02X}", escape, next))?;
+        out.push(original);
+    }
+    Ok(out)
+}
+
+/// COBS 编码，末尾追加 0x00
+fn cobs_encode(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0];
+    let mut code_index = 0;
+    let mut code = 1u8;
+    for &byte in data {
+        if byte != 0 {
+            out.push(byte);
+            code += 1;
+        }
+        if byte == 0 || code == 0xFF {
+            out[code_index] = code;
+            code_index = out.len();
+            out.push(0);
+            code = 1;
+        }
+    }
+    out[code_index] = code;
+    out.push(0);
+    out
+}
+
+/// COBS 解码，输入不含定界字节
+fn cobs_decode(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut pos = 0;
+    while pos < data.len() {
+        let code = data[pos] as usize;
+        let end = pos + code;
+        if end > data.len() {
+            return Err(format!("COBS 数据不完整: 第 {} 字节的编码 {} 超出帧长度", pos, code));
+        }
+        out.extend_from_slice(&data[pos + 1..end]);
+        pos = end;
+        if code < 0xFF && pos < data.len() {
+            out.push(0);
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples() -> Vec<Vec<u8>> {
+        vec![
+            Vec::new(),
+            vec![0x00],
+            vec![0x7E, 0x7D, 0xC0, 0xDB, 0x00, 0x01, 0x00],
+            (0..=255u8).cycle().take(600).collect(),
+            vec![0x01; 254],
+            vec![0x01; 255],
+        ]
+    }
+
+    #[test]
+    fn codecs_round_trip() {
+        for framing in [Framing::Slip, Framing::Hdlc(HdlcParams::default()), Framing::Cobs] {
+            for data in samples() {
+                let encoded = framing.encode(&data);
+                assert_eq!(encoded.last(), Some(&framing.delimiter()));
+                assert!(!encoded[..encoded.len() - 1].iter().skip(1).any(|b| *b == framing.delimiter()));
+                assert_eq!(framing.decode(&encoded).unwrap(), data, "{:?}", framing);
+            }
+        }
+    }
+
+    #[test]
+    fn escapes_delimiter_bytes() {
+        assert_eq!(Framing::Slip.encode(&[0xC0, 0xDB]), [0xC0, 0xDB, 0xDC, 0xDB, 0xDD, 0xC0]);
+        assert_eq!(
+            Framing::Hdlc(HdlcParams::default()).encode(&[0x7E, 0x7D]),
+            [0x7E, 0x7D, 0x5E, 0x7D, 0x5D, 0x7E],
+        );
+        assert_eq!(Framing::Cobs.encode(&[0x11, 0x22, 0x00, 0x33]), [0x03, 0x11, 0x22, 0x02, 0x33, 0x00]);
+    }
+
+    #[test]
+    fn malformed_frames_are_rejected() {
+        assert!(Framing::Slip.decode(&[0xC0, 0x01, 0xDB, 0x02, 0xC0]).is_err());
+        assert!(Framing::Cobs.decode(&[0x05, 0x11, 0x00]).is_err());
+        assert!(Framing::Hdlc(HdlcParams::default()).decode(&[0x7E, 0x01, 0x7E, 0x02, 0x7E]).is_err());
+        assert!(HdlcParams { flag: 0x7E, escape: 0x7D, xor: 0x03 }.validate().is_err());
+    }
+}
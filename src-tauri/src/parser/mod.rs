@@ -1,5 +1,25 @@
 mod protocol;
 mod parser;
+mod checksum;
+mod crc;
+mod encoding;
+mod stream;
+mod validate;
+mod exchange;
+mod encoder;
+mod framing;
+mod text;
+mod modbus;
+mod nmea;
 
 pub use protocol::*;
 pub use parser::*;
+pub use crc::CrcSpec;
+pub use encoding::parse_hex_string;
+pub use stream::*;
+pub use validate::*;
+pub use exchange::*;
+pub use encoder::*;
+pub use framing::*;
+pub use text::*;
+pub use modbus::*;
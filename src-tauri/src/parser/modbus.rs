@@ -0,0 +1,788 @@
+use serde::{Deserialize, Serialize};
+use super::checksum::{read_uint, write_uint};
+use super::parser::error_frame;
+use super::protocol::*;
+
+/// RTU 帧的最大长度
+const MAX_RTU_LENGTH: usize = 256;
+
+/// Modbus 传输模式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ModbusMode {
+    /// RTU：二进制帧，CRC-16/MODBUS 校验（低字节在前）
+    Rtu,
+    /// ASCII：以 ":" 开始、"\r\n" 结束的十六进制文本，LRC 校验
+    Ascii,
+}
+
+impl ModbusMode {
+    /// 校验类型
+    pub fn checksum_type(&self) -> ChecksumType {
+        match self {
+            ModbusMode::Rtu => ChecksumType::Crc16,
+            ModbusMode::Ascii => ChecksumType::Lrc8,
+        }
+    }
+
+    /// 由从站地址与 PDU 构造 ADU
+    pub fn wrap(&self, slave: u8, pdu: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![slave];
+        bytes.extend_from_slice(pdu);
+        self.checksum_type().append(&mut bytes, &ByteOrder::LittleEndian);
+
+        match self {
+            ModbusMode::Rtu => bytes,
+            ModbusMode::Ascii => {
+                let hex: String = bytes.iter().map(|b| format!("{:02X}", b)).collect();
+                format!(":{}\r\n", hex).into_bytes()
+            }
+        }
+    }
+}
+
+/// Modbus 报文类别
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ModbusKind {
+    /// 主站请求
+    Request,
+    /// 从站正常响应
+    Response,
+    /// 从站异常响应
+    Exception,
+}
+
+/// 解码后的 Modbus 报文
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModbusMessage {
+    /// 从站地址
+    pub slave: u8,
+    /// 功能码，异常响应为去掉 0x80 后的功能码
+    pub function: u8,
+    /// 报文类别
+    pub kind: ModbusKind,
+    /// 起始地址，读写多个寄存器时为读起始地址
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<u16>,
+    /// 数量，读写多个寄存器时为读数量
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<u16>,
+    /// 写起始地址（仅读写多个寄存器请求）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub write_address: Option<u16>,
+    /// 写数量（仅读写多个寄存器请求）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub write_quantity: Option<u16>,
+    /// 线圈状态（0/1）或寄存器值
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<u16>,
+    /// 异常码
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exception: Option<u8>,
+    /// PDU 原始数据，用于按响应格式重新解读
+    #[serde(skip)]
+    pdu: Vec<u8>,
+}
+
+/// 配对的请求与响应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModbusTransaction {
+    /// 请求
+    pub request: ModbusMessage,
+    /// 响应（含异常响应）
+    pub response: ModbusMessage,
+    /// 从请求到响应的耗时（毫秒）
+    pub elapsed: i64,
+}
+
+impl ModbusTransaction {
+    /// 配对请求与响应，读线圈响应按请求数量截去补齐的位
+    pub fn new(request: ModbusMessage, mut response: ModbusMessage, elapsed: i64) -> Self {
+        if let (ModbusKind::Response, Some(quantity)) = (response.kind, request.quantity) {
+            if is_coil_function(response.function) {
+                response.values.truncate(quantity as usize);
+                response.quantity = Some(quantity);
+            }
+        }
+        Self { request, response, elapsed }
+    }
+}
+
+/// 请求与响应配对
+///
+/// 记录最近一个请求，之后同一从站、同一功能码的响应与之配对。
+#[derive(Debug, Default)]
+pub struct ModbusTracker {
+    /// 等待响应的请求及其时间戳
+    pending: Option<(ModbusMessage, i64)>,
+}
+
+impl ModbusTracker {
+    /// 记录一个报文，与等待中的请求配对成功时返回配对结果
+    ///
+    /// 写单个线圈或寄存器的响应与请求相同，解码为请求时按响应重新解读。
+    pub fn observe(&mut self, message: &ModbusMessage, timestamp: i64) -> Option<ModbusTransaction> {
+        let matches = self.pending.as_ref()
+            .is_some_and(|(request, _)| request.slave == message.slave && request.function == message.function);
+
+        let response = match message.kind {
+            ModbusKind::Request if matches => message.as_response(),
+            ModbusKind::Request => None,
+            _ if matches => Some(message.clone()),
+            _ => return None,
+        };
+
+        match response {
+            Some(response) => {
+                let (request, sent_at) = self.pending.take()?;
+                Some(ModbusTransaction::new(request, response, timestamp - sent_at))
+            }
+            None => {
+                self.pending = Some((message.clone(), timestamp));
+                None
+            }
+        }
+    }
+
+    /// 按等待中的请求推断报文类别：同一从站、同一功能码的请求等待响应时按响应解读
+    pub fn hint(&self, slave: u8, function: u8) -> Option<ModbusKind> {
+        self.pending.as_ref()
+            .filter(|(request, _)| request.slave == slave && request.function == function)
+            .map(|_| ModbusKind::Response)
+    }
+
+    /// 清除等待中的请求
+    pub fn reset(&mut self) {
+        self.pending = None;
+    }
+}
+
+/// 主站请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModbusRequest {
+    /// 从站地址，0 为广播（仅写操作）
+    pub slave: u8,
+    /// 功能码：1–6、15、16、23
+    pub function: u8,
+    /// 起始地址，读写多个寄存器时为读起始地址
+    pub address: u16,
+    /// 读数量（功能码 1–4、23）
+    #[serde(default)]
+    pub quantity: u16,
+    /// 写起始地址（仅功能码 23）
+    #[serde(default)]
+    pub write_address: u16,
+    /// 写入的线圈状态（非 0 为 ON）或寄存器值
+    #[serde(default)]
+    pub values: Vec<u16>,
+}
+
+impl ModbusRequest {
+    /// 构造 PDU，数量超出协议限制时返回错误
+    pub fn to_pdu(&self) -> Result<Vec<u8>, String> {
+        if self.slave > 247 {
+            return Err(format!("从站地址须为 0–247, 实际 {}", self.slave));
+        }
+        if self.slave == 0 && matches!(self.function, 1..=4 | 23) {
+            return Err("广播地址仅支持写操作".to_string());
+        }
+
+        let mut pdu = vec![self.function];
+        let count = self.values.len();
+
+        match self.function {
+            1 | 2 => {
+                check_quantity("读取数量", self.quantity as usize, 2000)?;
+                pdu.extend(pack_registers(&[self.address, self.quantity]));
+            }
+            3 | 4 => {
+                check_quantity("读取数量", self.quantity as usize, 125)?;
+                pdu.extend(pack_registers(&[self.address, self.quantity]));
+            }
+            5 | 6 => {
+                let [value] = self.values[..] else {
+                    let target = if self.function == 5 { "线圈" } else { "寄存器" };
+                    return Err(format!("写单个{}须给出 1 个值, 实际 {} 个", target, count));
+                };
+                let value = match self.function {
+                    5 if value != 0 => 0xFF00,
+                    5 => 0x0000,
+                    _ => value,
+                };
+                pdu.extend(pack_registers(&[self.address, value]));
+            }
+            15 => {
+                check_quantity("写入数量", count, 1968)?;
+                let bytes = pack_coils(&self.values);
+                pdu.extend(pack_registers(&[self.address, count as u16]));
+                pdu.push(bytes.len() as u8);
+                pdu.extend(bytes);
+            }
+            16 => {
+                check_quantity("写入数量", count, 123)?;
+                pdu.extend(pack_registers(&[self.address, count as u16]));
+                pdu.push((count * 2) as u8);
+                pdu.extend(pack_registers(&self.values));
+            }
+            23 => {
+                check_quantity("读取数量", self.quantity as usize, 125)?;
+                check_quantity("写入数量", count, 121)?;
+                pdu.extend(pack_registers(&[self.address, self.quantity, self.write_address, count as u16]));
+                pdu.push((count * 2) as u8);
+                pdu.extend(pack_registers(&self.values));
+            }
+            _ => return Err(format!("不支持的功能码: {}", self.function)),
+        }
+        Ok(pdu)
+    }
+
+    /// 构造 ADU
+    pub fn encode(&self, mode: ModbusMode) -> Result<Vec<u8>, String> {
+        Ok(mode.wrap(self.slave, &self.to_pdu()?))
+    }
+}
+
+fn check_quantity(name: &str, count: usize, max: usize) -> Result<(), String> {
+    if (1..=max).contains(&count) {
+        Ok(())
+    } else {
+        Err(format!("{}须为 1–{}, 实际 {}", name, max, count))
+    }
+}
+
+/// 是否为支持的功能码
+fn is_supported(function: u8) -> bool {
+    matches!(function, 1..=6 | 15 | 16 | 23)
+}
+
+/// 是否为按位读写的功能码
+fn is_coil_function(function: u8) -> bool {
+    matches!(function, 1 | 2 | 5 | 15)
+}
+
+/// 功能码名称
+pub fn function_name(function: u8) -> &'static str {
+    match function {
+        1 => "读线圈",
+        2 => "读离散输入",
+        3 => "读保持寄存器",
+        4 => "读输入寄存器",
+        5 => "写单个线圈",
+        6 => "写单个寄存器",
+        15 => "写多个线圈",
+        16 => "写多个寄存器",
+        23 => "读写多个寄存器",
+        _ => "未知功能",
+    }
+}
+
+/// 异常码名称
+pub fn exception_name(code: u8) -> &'static str {
+    match code {
+        1 => "非法功能",
+        2 => "非法数据地址",
+        3 => "非法数据值",
+        4 => "从站设备故障",
+        5 => "确认",
+        6 => "从站设备忙",
+        8 => "存储奇偶性差错",
+        0x0A => "网关路径不可用",
+        0x0B => "网关目标设备响应失败",
+        _ => "未知异常",
+    }
+}
+
+/// 解码一个 ADU，返回报文与校验结果
+///
+/// `hint` 用于区分格式相同的请求与响应（如写单个寄存器），缺省按请求解读。
+pub fn decode_adu(data: &[u8], mode: ModbusMode, hint: Option<ModbusKind>) -> Result<(ModbusMessage, ChecksumResult), String> {
+    let (bytes, checksum) = unwrap_adu(data, mode)?;
+    let end = bytes.len() - mode.checksum_type().size();
+    let message = decode_pdu(bytes[0], &bytes[1..end], hint)?;
+    Ok((message, checksum))
+}
+
+/// 在接收数据中查找请求对应的响应（含异常响应），只接受校验正确的帧
+pub fn find_response(buffer: &[u8], mode: ModbusMode, request: &ModbusMessage) -> Option<ModbusMessage> {
+    let accept = |frame: &[u8]| {
+        decode_adu(frame, mode, Some(ModbusKind::Response)).ok()
+            .filter(|(_, checksum)| checksum.valid)
+            .map(|(message, _)| message)
+            .filter(|m| m.slave == request.slave && m.function == request.function && m.kind != ModbusKind::Request)
+    };
+
+    match mode {
+        ModbusMode::Rtu => (0..buffer.len()).find_map(|start| {
+            let frame = &buffer[start..];
+            rtu_frame_lengths(frame).into_iter()
+                .filter(|len| *len <= frame.len())
+                .find_map(|len| accept(&frame[..len]))
+        }),
+        ModbusMode::Ascii => (0..buffer.len())
+            .filter(|start| buffer[*start] == b':')
+            .find_map(|start| {
+                let end = start + buffer[start..].iter().position(|b| *b == b'\n')? + 1;
+                accept(&buffer[start..end])
+            }),
+    }
+}
+
+/// RTU 帧按功能码可能的长度（含地址与 CRC）
+///
+/// 依赖的字节数字段尚未到达时，给出读到该字段所需的长度。
+pub(super) fn rtu_frame_lengths(buffer: &[u8]) -> Vec<usize> {
+    let Some(&code) = buffer.get(1) else {
+        return vec![2];
+    };
+    // 由 index 处的字节数字段计算帧长
+    let counted = |index: usize| match buffer.get(index) {
+        Some(&count) => index + 1 + count as usize + 2,
+        None => index + 1,
+    };
+
+    let lengths = match code {
+        code if code & 0x80 != 0 => vec![5],
+        1..=4 => vec![8, counted(2)],
+        5 | 6 => vec![8],
+        15 | 16 => vec![counted(6), 8],
+        23 => vec![counted(10), counted(2)],
+        _ => Vec::new(),
+    };
+    lengths.into_iter().filter(|len| *len <= MAX_RTU_LENGTH).collect()
+}
+
+/// RTU 帧的 CRC 是否正确
+pub(super) fn rtu_checksum_valid(frame: &[u8]) -> bool {
+    frame.len() >= 4 && unwrap_adu(frame, ModbusMode::Rtu).is_ok_and(|(_, checksum)| checksum.valid)
+}
+
+/// 按内置 Modbus 解码器解析一帧
+///
+/// `tracker` 中有等待响应的请求时，同一从站、同一功能码的报文优先按响应解读。
+pub(super) fn parse_modbus(data: &[u8], protocol: &Protocol, mode: ModbusMode, tracker: Option<&ModbusTracker>) -> ParsedFrame {
+    let (bytes, checksum) = match unwrap_adu(data, mode) {
+        Ok(adu) => adu,
+        Err(e) => return error_frame(protocol, data, &e),
+    };
+    let unstuffed = (mode == ModbusMode::Ascii).then(|| bytes.clone());
+
+    let end = bytes.len() - checksum.checksum_type.size();
+    let hint = tracker.and_then(|t| t.hint(bytes[0], bytes[1] & 0x7F));
+    let decoded = decode_pdu(bytes[0], &bytes[1..end], hint);
+    let checksum_error = (!checksum.valid)
+        .then(|| format!("校验失败: 期望 0x{:X}, 实际 0x{:X}", checksum.expected, checksum.actual));
+
+    let (message, error) = match decoded {
+        Ok(message) => (Some(message), checksum_error),
+        Err(e) => (None, checksum_error.or(Some(e))),
+    };
+
+    ParsedFrame {
+        protocol_name: protocol.name.clone(),
+        raw_data: data.to_vec(),
+        fields: message.as_ref().map(|m| m.fields()).unwrap_or_default(),
+        valid: error.is_none(),
+        error,
+        checksum: Some(checksum),
+        variant: message.as_ref().map(|m| m.summary()),
+        unstuffed,
+        ambiguous: Vec::new(),
+        modbus: message,
+    }
+}
+
+/// 还原 ADU 的二进制数据（ASCII 模式解码十六进制文本）并校验
+fn unwrap_adu(data: &[u8], mode: ModbusMode) -> Result<(Vec<u8>, ChecksumResult), String> {
+    let bytes = match mode {
+        ModbusMode::Rtu => data.to_vec(),
+        ModbusMode::Ascii => decode_ascii(data)?,
+    };
+
+    let checksum_type = mode.checksum_type();
+    let size = checksum_type.size();
+    if bytes.len() < 2 + size {
+        return Err(format!("帧长度不足: {} 字节", bytes.len()));
+    }
+
+    let (body, tail) = bytes.split_at(bytes.len() - size);
+    let expected = checksum_type.compute(body);
+    let actual = read_uint(tail, &ByteOrder::LittleEndian);
+    let checksum = ChecksumResult {
+        checksum_type,
+        expected,
+        actual,
+        valid: expected == actual,
+    };
+    Ok((bytes, checksum))
+}
+
+/// 解码 ASCII 模式的十六进制文本
+fn decode_ascii(data: &[u8]) -> Result<Vec<u8>, String> {
+    let text = std::str::from_utf8(data).map_err(|_| "包含非 ASCII 字符".to_string())?;
+    let hex = text.trim_end_matches(['\r', '\n'])
+        .strip_prefix(':')
+        .ok_or("缺少起始符 \":\"")?;
+    if !hex.len().is_multiple_of(2) {
+        return Err("十六进制字符个数须为偶数".to_string());
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| format!("无效的十六进制字符: {}", &hex[i..i + 2])))
+        .collect()
+}
+
+/// 解码 PDU
+fn decode_pdu(slave: u8, pdu: &[u8], hint: Option<ModbusKind>) -> Result<ModbusMessage, String> {
+    let (&code, body) = pdu.split_first().ok_or("缺少功能码")?;
+    let base = ModbusMessage {
+        slave,
+        function: code & 0x7F,
+        kind: ModbusKind::Request,
+        address: None,
+        quantity: None,
+        write_address: None,
+        write_quantity: None,
+        values: Vec::new(),
+        exception: None,
+        pdu: pdu.to_vec(),
+    };
+
+    if code & 0x80 != 0 {
+        let [exception] = body[..] else {
+            return Err(format!("异常响应长度应为 2 字节, 实际 {} 字节", pdu.len()));
+        };
+        return Ok(ModbusMessage { kind: ModbusKind::Exception, exception: Some(exception), ..base });
+    }
+    if !is_supported(code) {
+        return Err(format!("不支持的功能码: 0x{:02X}", code));
+    }
+
+    let decoded = match hint {
+        Some(ModbusKind::Response) => decode_response(&base, body).or_else(|| decode_request(&base, body)),
+        _ => decode_request(&base, body).or_else(|| decode_response(&base, body)),
+    };
+    decoded.ok_or_else(|| format!("{}报文长度 {} 字节与格式不符", function_name(code), pdu.len()))
+}
+
+/// 按请求格式解读
+fn decode_request(base: &ModbusMessage, body: &[u8]) -> Option<ModbusMessage> {
+    let word = |index: usize| u16::from_be_bytes([body[index], body[index + 1]]);
+    let counted = |index: usize| body.len() > index && body.len() == index + 1 + body[index] as usize;
+    let mut message = base.clone();
+
+    match base.function {
+        1..=4 if body.len() == 4 => {
+            message.address = Some(word(0));
+            message.quantity = Some(word(2));
+        }
+        5 | 6 if body.len() == 4 => {
+            message.address = Some(word(0));
+            message.values = vec![single_value(base.function, word(2))?];
+        }
+        15 | 16 if counted(4) => {
+            message.address = Some(word(0));
+            message.quantity = Some(word(2));
+            message.values = unpack_values(base.function, &body[5..], word(2))?;
+        }
+        23 if counted(8) => {
+            message.address = Some(word(0));
+            message.quantity = Some(word(2));
+            message.write_address = Some(word(4));
+            message.write_quantity = Some(word(6));
+            message.values = unpack_values(base.function, &body[9..], word(6))?;
+        }
+        _ => return None,
+    }
+    Some(message)
+}
+
+/// 按响应格式解读
+fn decode_response(base: &ModbusMessage, body: &[u8]) -> Option<ModbusMessage> {
+    let word = |index: usize| u16::from_be_bytes([body[index], body[index + 1]]);
+    let counted = !body.is_empty() && body.len() == 1 + body[0] as usize;
+    let mut message = ModbusMessage { kind: ModbusKind::Response, ..base.clone() };
+
+    match base.function {
+        1 | 2 if counted => {
+            message.values = unpack_coils(&body[1..], body[0] as usize * 8);
+        }
+        3 | 4 | 23 if counted && body[0].is_multiple_of(2) => {
+            message.quantity = Some(body[0] as u16 / 2);
+            message.values = unpack_registers(&body[1..]);
+        }
+        5 | 6 if body.len() == 4 => {
+            message.address = Some(word(0));
+            message.values = vec![single_value(base.function, word(2))?];
+        }
+        15 | 16 if body.len() == 4 => {
+            message.address = Some(word(0));
+            message.quantity = Some(word(2));
+        }
+        _ => return None,
+    }
+    Some(message)
+}
+
+/// 写单个线圈或寄存器的值，线圈只接受 0xFF00 与 0x0000
+fn single_value(function: u8, value: u16) -> Option<u16> {
+    match (function, value) {
+        (5, 0xFF00) => Some(1),
+        (5, 0x0000) => Some(0),
+        (5, _) => None,
+        _ => Some(value),
+    }
+}
+
+/// 按数量解出线圈状态或寄存器值，字节数与数量不符时返回 `None`
+fn unpack_values(function: u8, data: &[u8], quantity: u16) -> Option<Vec<u16>> {
+    let quantity = quantity as usize;
+    if is_coil_function(function) {
+        (data.len() == quantity.div_ceil(8)).then(|| unpack_coils(data, quantity))
+    } else {
+        (data.len() == quantity * 2).then(|| unpack_registers(data))
+    }
+}
+
+/// 解出线圈状态，每字节低位在前
+fn unpack_coils(data: &[u8], count: usize) -> Vec<u16> {
+    (0..count.min(data.len() * 8))
+        .map(|i| ((data[i / 8] >> (i % 8)) & 1) as u16)
+        .collect()
+}
+
+/// 打包线圈状态，每字节低位在前
+fn pack_coils(values: &[u16]) -> Vec<u8> {
+    let mut bytes = vec![0u8; values.len().div_ceil(8)];
+    for (i, value) in values.iter().enumerate() {
+        if *value != 0 {
+            bytes[i / 8] |= 1 << (i % 8);
+        }
+    }
+    bytes
+}
+
+fn unpack_registers(data: &[u8]) -> Vec<u16> {
+    data.chunks_exact(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]])).collect()
+}
+
+fn pack_registers(values: &[u16]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_be_bytes()).collect()
+}
+
+impl ModbusMessage {
+    /// 摘要，如 "读保持寄存器 请求"
+    pub fn summary(&self) -> String {
+        let kind = match self.kind {
+            ModbusKind::Request => "请求",
+            ModbusKind::Response => "响应",
+            ModbusKind::Exception => "异常响应",
+        };
+        format!("{} {}", function_name(self.function), kind)
+    }
+
+    /// 按响应格式重新解读，无法解读为响应时返回 `None`
+    fn as_response(&self) -> Option<ModbusMessage> {
+        decode_pdu(self.slave, &self.pdu, Some(ModbusKind::Response)).ok()
+            .filter(|m| m.kind != ModbusKind::Request)
+    }
+
+    /// 转为解析结果字段
+    fn fields(&self) -> Vec<ParsedField> {
+        let code = match self.kind {
+            ModbusKind::Exception => self.function | 0x80,
+            _ => self.function,
+        };
+        let mut fields = vec![
+            number_field("slave", "从站地址", FieldType::Uint8, self.slave as u64),
+            enum_field(number_field("function", "功能码", FieldType::Uint8, code as u64), function_name(self.function)),
+        ];
+
+        if let Some(exception) = self.exception {
+            let field = number_field("exception", "异常码", FieldType::Uint8, exception as u64);
+            fields.push(enum_field(field, exception_name(exception)));
+        }
+
+        let numbers = [
+            ("address", "起始地址", self.address),
+            ("quantity", "数量", self.quantity),
+            ("writeAddress", "写起始地址", self.write_address),
+            ("writeQuantity", "写数量", self.write_quantity),
+        ];
+        for (name, description, value) in numbers {
+            if let Some(value) = value {
+                fields.push(number_field(name, description, FieldType::Uint16, value as u64));
+            }
+        }
+
+        if !self.values.is_empty() {
+            fields.push(self.values_field());
+        }
+        fields
+    }
+
+    /// 线圈状态或寄存器值，子字段描述中给出对应地址
+    fn values_field(&self) -> ParsedField {
+        let coils = is_coil_function(self.function);
+        let base_address = match self.function {
+            23 => self.write_address.filter(|_| self.kind == ModbusKind::Request).or(self.address),
+            _ => self.address,
+        };
+
+        let children = self.values.iter().enumerate().map(|(index, value)| {
+            let name = format!("values[{}]", index);
+            let mut field = if coils {
+                ParsedField {
+                    value: FieldValue::Bool(*value != 0),
+                    display: Some((*value != 0).to_string()),
+                    ..number_field(&name, "", FieldType::Bool, *value as u64)
+                }
+            } else {
+                number_field(&name, "", FieldType::Uint16, *value as u64)
+            };
+            field.description = base_address.map(|address| format!("地址 {}", address as usize + index));
+            field
+        }).collect();
+
+        let raw_bytes = if coils { pack_coils(&self.values) } else { pack_registers(&self.values) };
+        ParsedField {
+            name: "values".to_string(),
+            field_type: if coils { FieldType::Bool } else { FieldType::Uint16 },
+            value: FieldValue::Bytes(raw_bytes.clone()),
+            raw_bytes,
+            display: Some(format!("[{} 项]", self.values.len())),
+            raw_value: None,
+            scaled_value: None,
+            unit: None,
+            description: Some(if coils { "线圈状态" } else { "寄存器值" }.to_string()),
+            children,
+        }
+    }
+}
+
+/// 构造数值字段，原始字节按大端序给出
+fn number_field(name: &str, description: &str, field_type: FieldType, value: u64) -> ParsedField {
+    let size = field_type.size().unwrap_or(1);
+    ParsedField {
+        name: name.to_string(),
+        raw_bytes: write_uint(value, size, &ByteOrder::BigEndian),
+        field_type,
+        value: FieldValue::Unsigned(value),
+        display: Some(value.to_string()),
+        raw_value: Some(value as f64),
+        scaled_value: None,
+        unit: None,
+        description: (!description.is_empty()).then(|| description.to_string()),
+        children: Vec::new(),
+    }
+}
+
+/// 将数值字段转为带名称的枚举字段
+fn enum_field(field: ParsedField, label: &str) -> ParsedField {
+    let value = field.raw_value.unwrap_or_default() as u64;
+    ParsedField {
+        value: FieldValue::Enum { value, label: label.to_string() },
+        display: Some(format!("0x{:02X} {}", value, label)),
+        ..field
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::{ProtocolParser, StreamDecoder};
+
+    fn protocol(mode: ModbusMode) -> Protocol {
+        let mut protocol = Protocol::new("Modbus");
+        protocol.decoder = Some(BuiltinDecoder::Modbus { mode });
+        protocol
+    }
+
+    #[test]
+    fn rtu_frame_uses_crc16_modbus_low_byte_first() {
+        // 读保持寄存器：从站 1，地址 0，数量 10
+        let frame = [0x01, 0x03, 0x00, 0x00, 0x00, 0x0A, 0xC5, 0xCD];
+        assert_eq!(ModbusMode::Rtu.wrap(0x01, &frame[1..6]), frame);
+
+        let (message, checksum) = decode_adu(&frame, ModbusMode::Rtu, None).unwrap();
+        assert!(checksum.valid);
+        assert_eq!(checksum.expected, 0xCDC5);
+        assert_eq!(message.kind, ModbusKind::Request);
+        assert_eq!((message.address, message.quantity), (Some(0), Some(10)));
+
+        let mut corrupted = frame;
+        corrupted[7] ^= 0xFF;
+        let parsed = parse_modbus(&corrupted, &protocol(ModbusMode::Rtu), ModbusMode::Rtu, None);
+        assert!(!parsed.valid);
+        assert!(parsed.checksum.is_some_and(|c| !c.valid));
+    }
+
+    #[test]
+    fn ascii_frame_uses_lrc() {
+        let frame = b":010300000001FB\r\n";
+        assert_eq!(ModbusMode::Ascii.wrap(0x01, &[0x03, 0x00, 0x00, 0x00, 0x01]), frame);
+
+        let parsed = parse_modbus(frame, &protocol(ModbusMode::Ascii), ModbusMode::Ascii, None);
+        assert!(parsed.valid);
+        let checksum = parsed.checksum.unwrap();
+        assert_eq!((checksum.checksum_type, checksum.expected), (ChecksumType::Lrc8, 0xFB));
+        assert_eq!(parsed.unstuffed.unwrap(), [0x01, 0x03, 0x00, 0x00, 0x00, 0x01, 0xFB]);
+
+        let parsed = parse_modbus(b":010300000001FA\r\n", &protocol(ModbusMode::Ascii), ModbusMode::Ascii, None);
+        assert!(!parsed.valid);
+    }
+
+    #[test]
+    fn pending_request_resolves_coil_response_with_request_shape() {
+        let protocol = protocol(ModbusMode::Rtu);
+        // 读 19 个线圈的响应字节数为 3，数据部分与读请求同为 4 字节
+        let request = ModbusMode::Rtu.wrap(0x01, &[0x01, 0x00, 0x13, 0x00, 0x13]);
+        let response = ModbusMode::Rtu.wrap(0x01, &[0x01, 0x03, 0xCD, 0x6B, 0x05]);
+
+        let alone = parse_modbus(&response, &protocol, ModbusMode::Rtu, None);
+        assert_eq!(alone.modbus.unwrap().kind, ModbusKind::Request);
+
+        let mut tracker = ModbusTracker::default();
+        let request = parse_modbus(&request, &protocol, ModbusMode::Rtu, Some(&tracker)).modbus.unwrap();
+        assert_eq!(request.kind, ModbusKind::Request);
+        assert!(tracker.observe(&request, 0).is_none());
+        assert_eq!(tracker.hint(0x01, 0x01), Some(ModbusKind::Response));
+        assert_eq!(tracker.hint(0x02, 0x01), None);
+
+        let parsed = parse_modbus(&response, &protocol, ModbusMode::Rtu, Some(&tracker));
+        assert_eq!(parsed.variant.as_deref(), Some("读线圈 响应"));
+        let message = parsed.modbus.unwrap();
+        assert_eq!(message.kind, ModbusKind::Response);
+        assert_eq!(message.values.len(), 24);
+
+        let transaction = tracker.observe(&message, 5).unwrap();
+        assert_eq!(transaction.response.values.len(), 19);
+        assert_eq!(transaction.elapsed, 5);
+        assert_eq!(tracker.hint(0x01, 0x01), None);
+    }
+
+    #[test]
+    fn stream_pairs_request_and_response_in_same_chunk() {
+        let protocol = protocol(ModbusMode::Rtu);
+        let mut parser = ProtocolParser::new();
+        parser.set_active_protocol(Some(protocol.id.clone()));
+        parser.register_protocol(protocol);
+
+        let mut data = ModbusMode::Rtu.wrap(0x01, &[0x01, 0x00, 0x13, 0x00, 0x13]);
+        data.extend(ModbusMode::Rtu.wrap(0x01, &[0x01, 0x03, 0xCD, 0x6B, 0x05]));
+
+        let mut tracker = ModbusTracker::default();
+        let frames = StreamDecoder::new().push(&data, &parser, &mut tracker, 0);
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].0.variant.as_deref(), Some("读线圈 请求"));
+        assert!(frames[0].1.is_none());
+        assert_eq!(frames[1].0.variant.as_deref(), Some("读线圈 响应"));
+        let transaction = frames[1].1.as_ref().unwrap();
+        assert_eq!(transaction.request.quantity, Some(0x13));
+        assert_eq!(transaction.response.values.len(), 0x13);
+    }
+}
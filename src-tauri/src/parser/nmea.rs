@@ -0,0 +1,550 @@
+use super::encoding::format_timestamp;
+use super::parser::error_frame;
+use super::protocol::*;
+
+/// 按内置 NMEA 0183 解码器解析一条语句
+///
+/// 校验 `*hh` 异或校验值（语句未携带时不校验）；GGA、RMC、GSV、GSA、VTG 解码为带类型的字段，
+/// 其他语句按逗号拆分为字符串字段。发送方标识不限，如 GP、GN、GL、BD。
+pub(super) fn parse_nmea(data: &[u8], protocol: &Protocol) -> ParsedFrame {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return error_frame(protocol, data, "包含非 ASCII 字符");
+    };
+    let line = text.trim_end_matches(['\r', '\n']);
+    let Some(sentence) = line.strip_prefix('$') else {
+        return error_frame(protocol, data, "缺少起始符 \"$\"");
+    };
+
+    let (body, checksum) = match sentence.split_once('*') {
+        Some((body, hex)) => {
+            let is_hex = hex.len() == 2 && hex.bytes().all(|b| b.is_ascii_hexdigit());
+            let Some(actual) = is_hex.then(|| u8::from_str_radix(hex, 16).ok()).flatten() else {
+                return error_frame(protocol, data, &format!("无效的校验值: {}", hex));
+            };
+            let expected = ChecksumType::Xor8.compute(body.as_bytes());
+            let checksum = ChecksumResult {
+                checksum_type: ChecksumType::Xor8,
+                expected,
+                actual: actual as u64,
+                valid: expected == actual as u64,
+            };
+            (body, Some(checksum))
+        }
+        None => (sentence, None),
+    };
+
+    let mut parts = body.split(',');
+    let address = parts.next().unwrap_or_default();
+    let values: Vec<&str> = parts.collect();
+    let Some((talker, kind)) = split_address(address) else {
+        return error_frame(protocol, data, &format!("无效的语句地址: {}", address));
+    };
+
+    let mut fields = SentenceFields::new(&values);
+    fields.text("talker", "发送方标识", talker);
+    fields.text("sentence", "语句类型", kind);
+    match kind {
+        "GGA" => fields.gga(),
+        "RMC" => fields.rmc(),
+        "GSV" => fields.gsv(),
+        "GSA" => fields.gsa(),
+        "VTG" => fields.vtg(),
+        _ => fields.generic(),
+    }
+
+    let error = checksum.as_ref()
+        .filter(|c| !c.valid)
+        .map(|c| format!("校验失败: 期望 0x{:02X}, 实际 0x{:02X}", c.expected, c.actual));
+
+    ParsedFrame {
+        protocol_name: protocol.name.clone(),
+        raw_data: data.to_vec(),
+        fields: fields.fields,
+        valid: error.is_none(),
+        error,
+        checksum,
+        variant: Some(sentence_name(kind).map_or_else(|| kind.to_string(), |name| format!("{} {}", kind, name))),
+        unstuffed: None,
+        ambiguous: Vec::new(),
+        modbus: None,
+    }
+}
+
+/// 拆分语句地址为发送方标识与语句类型，专有语句（P 开头）的发送方标识为 "P"
+fn split_address(address: &str) -> Option<(&str, &str)> {
+    if address.len() < 3 || !address.bytes().all(|b| b.is_ascii_uppercase() || b.is_ascii_digit()) {
+        return None;
+    }
+    match address.strip_prefix('P') {
+        Some(rest) => Some(("P", rest)),
+        None => Some(address.split_at(2)),
+    }
+}
+
+/// 语句类型名称
+fn sentence_name(kind: &str) -> Option<&'static str> {
+    match kind {
+        "GGA" => Some("定位数据"),
+        "RMC" => Some("推荐最小定位信息"),
+        "GSV" => Some("可见卫星"),
+        "GSA" => Some("精度因子与有效卫星"),
+        "VTG" => Some("地面航向与速度"),
+        _ => None,
+    }
+}
+
+/// GGA 定位质量
+fn fix_quality(value: u64) -> Option<&'static str> {
+    match value {
+        0 => Some("无效"),
+        1 => Some("GPS 定位"),
+        2 => Some("差分定位"),
+        3 => Some("PPS 定位"),
+        4 => Some("RTK 固定解"),
+        5 => Some("RTK 浮点解"),
+        6 => Some("航位推算"),
+        7 => Some("手动输入"),
+        8 => Some("模拟"),
+        _ => None,
+    }
+}
+
+/// GSA 定位类型
+fn fix_type(value: u64) -> Option<&'static str> {
+    match value {
+        1 => Some("未定位"),
+        2 => Some("2D 定位"),
+        3 => Some("3D 定位"),
+        _ => None,
+    }
+}
+
+/// RMC、VTG 定位模式
+fn position_mode(value: &str) -> Option<&'static str> {
+    match value {
+        "A" => Some("自主定位"),
+        "D" => Some("差分定位"),
+        "E" => Some("航位推算"),
+        "F" => Some("RTK 浮点解"),
+        "M" => Some("手动输入"),
+        "N" => Some("无效"),
+        "R" => Some("RTK 固定解"),
+        "S" => Some("模拟"),
+        _ => None,
+    }
+}
+
+/// RMC 定位状态
+fn status(value: &str) -> Option<&'static str> {
+    match value {
+        "A" => Some("有效"),
+        "V" => Some("无效"),
+        _ => None,
+    }
+}
+
+/// GSA 选择模式
+fn selection_mode(value: &str) -> Option<&'static str> {
+    match value {
+        "A" => Some("自动"),
+        "M" => Some("手动"),
+        _ => None,
+    }
+}
+
+/// 语句字段收集器，空字段视为无数据，不输出
+struct SentenceFields<'a> {
+    values: &'a [&'a str],
+    fields: Vec<ParsedField>,
+}
+
+impl<'a> SentenceFields<'a> {
+    fn new(values: &'a [&'a str]) -> Self {
+        Self { values, fields: Vec::new() }
+    }
+
+    /// 取第 index 个字段，空字段返回 `None`
+    fn get(&self, index: usize) -> Option<&'a str> {
+        self.values.get(index).copied().filter(|v| !v.is_empty())
+    }
+
+    /// GGA：时间、位置、定位质量、卫星数、HDOP、海拔、大地水准面差距与差分信息
+    fn gga(&mut self) {
+        self.time(0);
+        self.coordinate(1, "latitude", "纬度");
+        self.coordinate(3, "longitude", "经度");
+        self.enumerated(5, "quality", "定位质量", fix_quality);
+        self.integer(6, "satellites", "使用卫星数", FieldType::Uint8, None);
+        self.number(7, "hdop", "水平精度因子", None);
+        self.number(8, "altitude", "海拔高度", Some("m"));
+        self.number(10, "geoidSeparation", "大地水准面差距", Some("m"));
+        self.number(12, "dgpsAge", "差分数据龄期", Some("s"));
+        self.integer(13, "dgpsStation", "差分基站 ID", FieldType::Uint16, None);
+    }
+
+    /// RMC：时间、状态、位置、航速、航向、日期、磁偏角与定位模式
+    fn rmc(&mut self) {
+        self.time(0);
+        self.labeled(1, "status", "定位状态", status);
+        self.coordinate(2, "latitude", "纬度");
+        self.coordinate(4, "longitude", "经度");
+        self.number(6, "speedKnots", "地面速度", Some("kn"));
+        self.number(7, "course", "地面航向", Some("°"));
+        self.datetime(8, 0);
+        if let Some(raw) = self.get(9) {
+            let west = self.get(10) == Some("W");
+            let value = raw.parse::<f64>().ok().map(|v| if west { -v } else { v });
+            self.push_float("magneticVariation", "磁偏角（西偏为负）", raw, value, Some("°"));
+        }
+        self.labeled(11, "mode", "定位模式", position_mode);
+    }
+
+    /// GSV：语句总数、序号、可见卫星数及每颗卫星的编号、仰角、方位角与信噪比
+    fn gsv(&mut self) {
+        self.integer(0, "totalMessages", "语句总数", FieldType::Uint8, None);
+        self.integer(1, "messageNumber", "语句序号", FieldType::Uint8, None);
+        self.integer(2, "satellitesInView", "可见卫星数", FieldType::Uint8, None);
+
+        // NMEA 4.10 在卫星信息之后追加信号 ID
+        let remaining = self.values.len().saturating_sub(3);
+        let groups = remaining / 4;
+        let children = (0..groups)
+            .filter_map(|group| {
+                let base = 3 + group * 4;
+                let mut satellite = SentenceFields::new(&self.values[base..base + 4]);
+                satellite.integer(0, "prn", "卫星编号", FieldType::Uint16, None);
+                satellite.integer(1, "elevation", "仰角", FieldType::Uint8, Some("°"));
+                satellite.integer(2, "azimuth", "方位角", FieldType::Uint16, Some("°"));
+                satellite.integer(3, "snr", "信噪比", FieldType::Uint8, Some("dB-Hz"));
+                let raw = self.values[base..base + 4].join(",");
+                (!satellite.fields.is_empty())
+                    .then(|| container(&format!("satellites[{}]", group), "卫星", FieldType::Struct, &raw, satellite.fields))
+            })
+            .collect::<Vec<_>>();
+        if !children.is_empty() {
+            let raw = self.values[3..3 + groups * 4].join(",");
+            self.fields.push(container("satellites", "卫星信息", FieldType::Struct, &raw, children));
+        }
+        if remaining % 4 == 1 {
+            self.text_at(3 + groups * 4, "signalId", "信号 ID");
+        }
+    }
+
+    /// GSA：选择模式、定位类型、参与定位的卫星编号及 PDOP、HDOP、VDOP
+    fn gsa(&mut self) {
+        self.labeled(0, "selectionMode", "选择模式", selection_mode);
+        self.enumerated(1, "fixType", "定位类型", fix_type);
+
+        // 12 个卫星编号位置，空位不输出
+        let slots = &self.values[2.min(self.values.len())..14.min(self.values.len())];
+        let used: Vec<&str> = slots.iter().copied().filter(|v| !v.is_empty()).collect();
+        let mut satellites = SentenceFields::new(&used);
+        for index in 0..used.len() {
+            satellites.integer(index, &format!("satellites[{}]", index), "卫星编号", FieldType::Uint16, None);
+        }
+        if !satellites.fields.is_empty() {
+            let raw = slots.join(",");
+            self.fields.push(container("satellites", "参与定位的卫星", FieldType::Uint16, &raw, satellites.fields));
+        }
+
+        self.number(14, "pdop", "位置精度因子", None);
+        self.number(15, "hdop", "水平精度因子", None);
+        self.number(16, "vdop", "垂直精度因子", None);
+        self.text_at(17, "systemId", "系统 ID");
+    }
+
+    /// VTG：真北航向、磁北航向、地面速度（节与公里每小时）及定位模式
+    fn vtg(&mut self) {
+        self.number(0, "courseTrue", "真北航向", Some("°"));
+        self.number(2, "courseMagnetic", "磁北航向", Some("°"));
+        self.number(4, "speedKnots", "地面速度", Some("kn"));
+        self.number(6, "speedKmh", "地面速度", Some("km/h"));
+        self.labeled(8, "mode", "定位模式", position_mode);
+    }
+
+    /// 未知语句：各字段按字符串给出
+    fn generic(&mut self) {
+        for index in 0..self.values.len() {
+            self.text_at(index, &format!("field{}", index + 1), "字段");
+        }
+    }
+
+    /// 字符串字段
+    fn text(&mut self, name: &str, description: &str, raw: &str) {
+        let value = Some((FieldValue::String(raw.to_string()), raw.to_string()));
+        self.fields.push(sentence_field(name, description, FieldType::String, raw, value, None));
+    }
+
+    fn text_at(&mut self, index: usize, name: &str, description: &str) {
+        if let Some(raw) = self.get(index) {
+            self.text(name, description, raw);
+        }
+    }
+
+    /// 整数字段
+    fn integer(&mut self, index: usize, name: &str, description: &str, field_type: FieldType, unit: Option<&str>) {
+        if let Some(raw) = self.get(index) {
+            let value = raw.parse::<u64>().ok().map(|v| (FieldValue::Unsigned(v), v.to_string()));
+            self.fields.push(sentence_field(name, description, field_type, raw, value, unit));
+        }
+    }
+
+    /// 浮点字段
+    fn number(&mut self, index: usize, name: &str, description: &str, unit: Option<&str>) {
+        if let Some(raw) = self.get(index) {
+            self.push_float(name, description, raw, raw.parse::<f64>().ok(), unit);
+        }
+    }
+
+    fn push_float(&mut self, name: &str, description: &str, raw: &str, value: Option<f64>, unit: Option<&str>) {
+        let value = value.map(|v| (FieldValue::Float(v), v.to_string()));
+        self.fields.push(sentence_field(name, description, FieldType::Float64, raw, value, unit));
+    }
+
+    /// 带名称的整数字段
+    fn enumerated(&mut self, index: usize, name: &str, description: &str, label: fn(u64) -> Option<&'static str>) {
+        if let Some(raw) = self.get(index) {
+            let value = raw.parse::<u64>().ok().map(|value| match label(value) {
+                Some(label) => (FieldValue::Enum { value, label: label.to_string() }, format!("{} {}", value, label)),
+                None => (FieldValue::Unsigned(value), value.to_string()),
+            });
+            self.fields.push(sentence_field(name, description, FieldType::Uint8, raw, value, None));
+        }
+    }
+
+    /// 带名称的字符标志字段
+    fn labeled(&mut self, index: usize, name: &str, description: &str, label: fn(&str) -> Option<&'static str>) {
+        if let Some(raw) = self.get(index) {
+            let display = match label(raw) {
+                Some(label) => format!("{} {}", raw, label),
+                None => raw.to_string(),
+            };
+            let value = Some((FieldValue::String(raw.to_string()), display));
+            self.fields.push(sentence_field(name, description, FieldType::String, raw, value, None));
+        }
+    }
+
+    /// 经纬度，由 (d)ddmm.mmmm 与半球标识换算为度，南纬与西经为负
+    fn coordinate(&mut self, index: usize, name: &str, description: &str) {
+        let Some(raw) = self.get(index) else {
+            return;
+        };
+        let hemisphere = self.get(index + 1).unwrap_or_default();
+        let value = raw.parse::<f64>().ok()
+            .filter(|_| matches!(hemisphere, "N" | "S" | "E" | "W"))
+            .map(|v| {
+                let degrees = (v / 100.0).trunc();
+                let value = degrees + (v - degrees * 100.0) / 60.0;
+                if matches!(hemisphere, "S" | "W") { -value } else { value }
+            })
+            .map(|v| (FieldValue::Float(v), format!("{:.6}", v)));
+
+        let raw = format!("{},{}", raw, hemisphere);
+        self.fields.push(sentence_field(name, description, FieldType::Float64, &raw, value, Some("°")));
+    }
+
+    /// UTC 时间，hhmmss(.sss) 显示为 hh:mm:ss(.sss)
+    fn time(&mut self, index: usize) {
+        if let Some(raw) = self.get(index) {
+            let value = parse_time(raw).map(|_| {
+                let text = format!("{}:{}:{}", &raw[0..2], &raw[2..4], &raw[4..]);
+                (FieldValue::String(text.clone()), format!("{} UTC", text))
+            });
+            self.fields.push(sentence_field("time", "UTC 时间", FieldType::String, raw, value, None));
+        }
+    }
+
+    /// 日期 ddmmyy 与时间合并为 UTC 时间戳
+    fn datetime(&mut self, date_index: usize, time_index: usize) {
+        let Some(date) = self.get(date_index) else {
+            return;
+        };
+        let time = self.get(time_index).unwrap_or("000000");
+        let value = parse_datetime(date, time)
+            .map(|millis| (FieldValue::Timestamp(millis), format_timestamp(millis, true)));
+        self.fields.push(sentence_field("datetime", "UTC 日期时间", FieldType::TimestampMs, date, value, None));
+    }
+}
+
+/// 解析 hhmmss(.sss) 为当日毫秒数
+fn parse_time(raw: &str) -> Option<i64> {
+    if raw.len() < 6 || !raw.is_char_boundary(6) || !raw[..6].bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let hours: i64 = raw[0..2].parse().ok()?;
+    let minutes: i64 = raw[2..4].parse().ok()?;
+    let seconds: f64 = raw[4..].parse().ok()?;
+    if hours > 23 || minutes > 59 || seconds >= 61.0 {
+        return None;
+    }
+    Some((hours * 3600 + minutes * 60) * 1000 + (seconds * 1000.0).round() as i64)
+}
+
+/// 解析 ddmmyy 日期与时间为 Unix 时间戳（毫秒），两位年份 80 及以上视为 19xx
+fn parse_datetime(date: &str, time: &str) -> Option<i64> {
+    if date.len() != 6 || !date.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let day: u32 = date[0..2].parse().ok()?;
+    let month: u32 = date[2..4].parse().ok()?;
+    let year: i32 = date[4..6].parse().ok()?;
+    let year = if year >= 80 { 1900 + year } else { 2000 + year };
+
+    let midnight = chrono::NaiveDate::from_ymd_opt(year, month, day)?.and_hms_opt(0, 0, 0)?;
+    Some(midnight.and_utc().timestamp_millis() + parse_time(time)?)
+}
+
+/// 构造字段，`decoded` 为值与显示文本，为空时字段记为解码失败
+fn sentence_field(
+    name: &str,
+    description: &str,
+    field_type: FieldType,
+    raw: &str,
+    decoded: Option<(FieldValue, String)>,
+    unit: Option<&str>,
+) -> ParsedField {
+    let (value, display) = match decoded {
+        Some((value, display)) => (value, Some(display)),
+        None => (FieldValue::Error(FieldError::new(FieldErrorKind::InvalidEncoding, format!("无法解析: {}", raw))), None),
+    };
+    ParsedField {
+        name: name.to_string(),
+        field_type,
+        raw_bytes: raw.as_bytes().to_vec(),
+        raw_value: value.as_f64(),
+        value,
+        display,
+        scaled_value: None,
+        unit: unit.map(str::to_string),
+        description: Some(description.to_string()),
+        children: Vec::new(),
+    }
+}
+
+/// 构造包含子字段的字段
+fn container(name: &str, description: &str, field_type: FieldType, raw: &str, children: Vec<ParsedField>) -> ParsedField {
+    ParsedField {
+        name: name.to_string(),
+        field_type,
+        raw_bytes: raw.as_bytes().to_vec(),
+        value: FieldValue::Bytes(raw.as_bytes().to_vec()),
+        display: Some(format!("[{} 项]", children.len())),
+        raw_value: None,
+        scaled_value: None,
+        unit: None,
+        description: Some(description.to_string()),
+        children,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> ParsedFrame {
+        parse_nmea(line.as_bytes(), &Protocol::new("NMEA"))
+    }
+
+    fn field<'a>(frame: &'a ParsedFrame, name: &str) -> &'a ParsedField {
+        frame.fields.iter().find(|f| f.name == name).unwrap_or_else(|| panic!("缺少字段 {}", name))
+    }
+
+    fn value(frame: &ParsedFrame, name: &str) -> f64 {
+        field(frame, name).raw_value.unwrap()
+    }
+
+    fn display<'a>(frame: &'a ParsedFrame, name: &str) -> &'a str {
+        field(frame, name).display.as_deref().unwrap()
+    }
+
+    #[test]
+    fn gga_and_rmc_decode_position() {
+        let gga = parse("$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47\r\n");
+        assert!(gga.valid, "{:?}", gga.error);
+        assert!(gga.checksum.as_ref().is_some_and(|c| c.valid && c.actual == 0x47));
+        assert_eq!(gga.variant.as_deref(), Some("GGA 定位数据"));
+        assert_eq!(display(&gga, "talker"), "GP");
+        assert_eq!(display(&gga, "time"), "12:35:19 UTC");
+        assert!((value(&gga, "latitude") - 48.1173).abs() < 1e-9);
+        assert!((value(&gga, "longitude") - 11.516_666_666).abs() < 1e-6);
+        assert_eq!(display(&gga, "quality"), "1 GPS 定位");
+        assert_eq!(value(&gga, "satellites"), 8.0);
+        assert_eq!(value(&gga, "altitude"), 545.4);
+        assert!(gga.fields.iter().all(|f| f.name != "dgpsAge"));
+
+        let rmc = parse("$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A");
+        assert!(rmc.valid, "{:?}", rmc.error);
+        assert_eq!(display(&rmc, "status"), "A 有效");
+        assert_eq!(value(&rmc, "speedKnots"), 22.4);
+        assert_eq!(value(&rmc, "magneticVariation"), -3.1);
+        // 1994-03-23 12:35:19 UTC
+        assert_eq!(value(&rmc, "datetime"), 764_426_119_000.0);
+    }
+
+    #[test]
+    fn gsv_gsa_and_vtg_decode_satellites_and_course() {
+        let gsv = parse("$GPGSV,2,1,08,01,40,083,46,02,17,308,41,12,07,344,39,14,22,228,45*75");
+        assert!(gsv.valid, "{:?}", gsv.error);
+        assert_eq!(value(&gsv, "satellitesInView"), 8.0);
+        let satellites = field(&gsv, "satellites");
+        assert_eq!(satellites.children.len(), 4);
+        let last = &satellites.children[3];
+        assert_eq!(last.children.iter().map(|f| f.raw_value.unwrap()).collect::<Vec<_>>(), [14.0, 22.0, 228.0, 45.0]);
+
+        let gsa = parse("$GPGSA,A,3,04,05,,09,12,,,24,,,,,2.5,1.3,2.1*39");
+        assert!(gsa.valid, "{:?}", gsa.error);
+        assert_eq!(display(&gsa, "fixType"), "3 3D 定位");
+        let used = field(&gsa, "satellites").children.iter().map(|f| f.raw_value.unwrap()).collect::<Vec<_>>();
+        assert_eq!(used, [4.0, 5.0, 9.0, 12.0, 24.0]);
+        assert_eq!((value(&gsa, "pdop"), value(&gsa, "hdop"), value(&gsa, "vdop")), (2.5, 1.3, 2.1));
+
+        let vtg = parse("$GPVTG,054.7,T,034.4,M,005.5,N,010.2,K*48");
+        assert!(vtg.valid, "{:?}", vtg.error);
+        assert_eq!(value(&vtg, "courseTrue"), 54.7);
+        assert_eq!(value(&vtg, "courseMagnetic"), 34.4);
+        assert_eq!(value(&vtg, "speedKmh"), 10.2);
+    }
+
+    #[test]
+    fn other_talkers_and_southern_western_hemispheres() {
+        let rmc = parse("$GNRMC,001031.00,A,3354.5123,S,15112.3456,W,0.01,,010120,,,D*52");
+        assert!(rmc.valid, "{:?}", rmc.error);
+        assert_eq!(display(&rmc, "talker"), "GN");
+        assert!((value(&rmc, "latitude") + 33.908_538_333).abs() < 1e-6);
+        assert!((value(&rmc, "longitude") + 151.205_76).abs() < 1e-6);
+        assert_eq!(display(&rmc, "mode"), "D 差分定位");
+        assert!(rmc.fields.iter().all(|f| f.name != "course" && f.name != "magneticVariation"));
+
+        let gsa = parse("$BDGSA,A,3,,,,,,,,,,,,,,,*0D");
+        assert!(gsa.valid, "{:?}", gsa.error);
+        assert_eq!(display(&gsa, "talker"), "BD");
+        assert!(gsa.fields.iter().all(|f| f.name != "satellites" && f.name != "pdop"));
+    }
+
+    #[test]
+    fn empty_fields_are_omitted() {
+        let gga = parse("$GNGGA,,,,,,0,00,99.99,,,,,,*56");
+        assert!(gga.valid, "{:?}", gga.error);
+        let names = gga.fields.iter().map(|f| f.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, ["talker", "sentence", "quality", "satellites", "hdop"]);
+        assert_eq!(display(&gga, "quality"), "0 无效");
+    }
+
+    #[test]
+    fn bad_checksums_are_rejected() {
+        let corrupted = parse("$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*48");
+        assert!(!corrupted.valid);
+        assert!(corrupted.checksum.as_ref().is_some_and(|c| !c.valid && c.expected == 0x47));
+        // 解码字段仍然给出，便于排查
+        assert_eq!(value(&corrupted, "altitude"), 545.4);
+
+        for hex in ["+A", "4", "4G", "047"] {
+            let frame = parse(&format!("$GPVTG,054.7,T,034.4,M,005.5,N,010.2,K*{}", hex));
+            assert!(!frame.valid, "{}", hex);
+            assert!(frame.checksum.is_none(), "{}", hex);
+        }
+
+        assert!(!parse("GPGGA,123519*47").valid);
+        assert!(!parse("$gpgga,123519").valid);
+    }
+}
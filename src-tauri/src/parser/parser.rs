@@ -1,4 +1,10 @@
 use super::protocol::*;
+use super::checksum::{read_uint, verify_checksum};
+use super::encoding::{decode_ascii_hex, decode_bcd, format_timestamp, half_to_f64};
+use super::modbus::{parse_modbus, ModbusTracker};
+use super::nmea::parse_nmea;
+use super::text::parse_text;
+use regex::Regex;
 use std::collections::HashMap;
 use std::sync::Mutex;
 
@@ -8,6 +14,10 @@ pub struct ProtocolParser {
     protocols: HashMap<String, Protocol>,
     /// 当前激活的协议
     active_protocol: Option<String>,
+    /// 自动识别模式：逐帧在全部协议中选择得分最高的
+    auto_detect: bool,
+    /// 文本协议已编译的正则及对应的协议更新时间
+    patterns: HashMap<String, (i64, Regex)>,
 }
 
 impl ProtocolParser {
@@ -16,17 +26,24 @@ impl ProtocolParser {
         Self {
             protocols: HashMap::new(),
             active_protocol: None,
+            auto_detect: false,
+            patterns: HashMap::new(),
         }
     }
 
     /// 注册协议
     pub fn register_protocol(&mut self, protocol: Protocol) {
+        match protocol.text.as_ref().and_then(|text| text.compile().ok().flatten()) {
+            Some(regex) => self.patterns.insert(protocol.id.clone(), (protocol.updated_at, regex)),
+            None => self.patterns.remove(&protocol.id),
+        };
         self.protocols.insert(protocol.id.clone(), protocol);
     }
 
     /// 移除协议
     pub fn remove_protocol(&mut self, id: &str) {
         self.protocols.remove(id);
+        self.patterns.remove(id);
         if self.active_protocol.as_deref() == Some(id) {
             self.active_protocol = None;
         }
@@ -37,6 +54,21 @@ impl ProtocolParser {
         self.active_protocol = id;
     }
 
+    /// 获取当前激活的协议
+    pub fn active_protocol(&self) -> Option<&Protocol> {
+        self.protocols.get(self.active_protocol.as_ref()?)
+    }
+
+    /// 设置自动识别模式
+    pub fn set_auto_detect(&mut self, enabled: bool) {
+        self.auto_detect = enabled;
+    }
+
+    /// 是否处于自动识别模式
+    pub fn auto_detect(&self) -> bool {
+        self.auto_detect
+    }
+
     /// 获取所有协议
     pub fn get_protocols(&self) -> Vec<&Protocol> {
         self.protocols.values().collect()
@@ -47,237 +79,749 @@ impl ProtocolParser {
         self.protocols.get(id)
     }
 
-    /// 解析数据
+    /// 解析数据，自动识别模式下在全部协议中匹配
     pub fn parse(&self, data: &[u8]) -> Option<ParsedFrame> {
-        let protocol_id = self.active_protocol.as_ref()?;
-        let protocol = self.protocols.get(protocol_id)?;
+        if self.auto_detect {
+            return self.detect(data);
+        }
+
+        let protocol = self.active_protocol()?;
 
         Some(self.parse_with_protocol(data, protocol))
     }
 
+    /// 在全部协议中识别数据，未注册任何协议时返回 `None`
+    ///
+    /// 帧头、帧尾、长度字段与校验全部通过且字段完整的协议参与评分，取得分最高者；
+    /// 得分与之相同或接近的其他协议记入 `ambiguous`。没有协议匹配时返回错误结果。
+    pub fn detect(&self, data: &[u8]) -> Option<ParsedFrame> {
+        if self.protocols.is_empty() {
+            return None;
+        }
+
+        let candidates = self.protocols_by_name()
+            .into_iter()
+            .filter_map(|protocol| self.match_protocol(data, protocol, None))
+            .collect();
+
+        Some(best_match(candidates).unwrap_or_else(|| unmatched_frame("", data, "未匹配任何协议")))
+    }
+
+    /// 按名称排序的协议列表，保证得分相同时结果稳定
+    pub(super) fn protocols_by_name(&self) -> Vec<&Protocol> {
+        let mut protocols: Vec<&Protocol> = self.protocols.values().collect();
+        protocols.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
+        protocols
+    }
+
+    /// 按协议解析并评分，未完全匹配时返回 `None`
+    ///
+    /// 得分为命中的结构特征字节数：帧头与帧尾按长度计，校验值按两倍长度计，
+    /// 长度字段一致计 2 分，成帧编码解码成功计 1 分，
+    /// 无长度字段与帧尾时帧长恰为最小帧长计 1 分；文本协议按匹配到的已定义字段数计分，
+    /// 内置解码器按校验值两倍长度加 2 分计分。
+    pub(super) fn match_protocol(&self, data: &[u8], protocol: &Protocol, modbus: Option<&ModbusTracker>) -> Option<(u32, ParsedFrame)> {
+        if data.is_empty() || !protocol.can_frame() {
+            return None;
+        }
+
+        let frame = self.parse_paired(data, protocol, modbus);
+        if !frame.valid || frame.fields.iter().any(has_error) {
+            return None;
+        }
+
+        if protocol.text.is_some() {
+            let known = frame.fields.iter()
+                .filter(|f| protocol.fields.iter().any(|d| d.name == f.name))
+                .count();
+            return Some((known as u32, frame));
+        }
+
+        if protocol.decoder.is_some() {
+            let checksum_len = frame.checksum.as_ref().map(|c| c.checksum_type.size()).unwrap_or(0);
+            return Some((checksum_len as u32 * 2 + 2, frame));
+        }
+
+        let header_len = protocol.header.as_ref().map(|h| h.len()).unwrap_or(0);
+        let footer_len = protocol.footer.as_ref().map(|f| f.len()).unwrap_or(0);
+        let checksum_len = frame.checksum.as_ref().map(|c| c.checksum_type.size()).unwrap_or(0);
+
+        let data_len = frame.unstuffed.as_ref().map(|d| d.len()).unwrap_or(data.len());
+
+        let mut score = header_len + footer_len + checksum_len * 2;
+        if protocol.framing.is_some() {
+            score += 1;
+        }
+        if protocol.length_field.is_some() {
+            score += 2;
+        } else if footer_len == 0 && Some(data_len) == protocol.frame_length_for(frame.unstuffed.as_deref().unwrap_or(data)) {
+            score += 1;
+        }
+        Some((score as u32, frame))
+    }
+
     /// 使用指定协议解析数据
     pub fn parse_with_protocol(&self, data: &[u8], protocol: &Protocol) -> ParsedFrame {
+        self.parse_paired(data, protocol, None)
+    }
+
+    /// 使用指定协议解析数据，内置 Modbus 解码器参考 `modbus` 中等待响应的请求
+    pub(super) fn parse_paired(&self, data: &[u8], protocol: &Protocol, modbus: Option<&ModbusTracker>) -> ParsedFrame {
+        match protocol.decoder {
+            Some(BuiltinDecoder::Modbus { mode }) => return parse_modbus(data, protocol, mode, modbus),
+            Some(BuiltinDecoder::Nmea) => return parse_nmea(data, protocol),
+            None => {}
+        }
+
+        if let Some(ref text) = protocol.text {
+            let pattern = self.patterns.get(&protocol.id)
+                .filter(|(updated_at, _)| *updated_at == protocol.updated_at)
+                .map(|(_, regex)| regex);
+            return parse_text(data, protocol, text, pattern);
+        }
+
+        let Some(ref framing) = protocol.framing else {
+            return self.parse_frame(data, protocol);
+        };
+
+        // 帧头、字段与校验均作用于解除转义后的数据
+        match framing.decode(data) {
+            Ok(unstuffed) => {
+                let mut frame = self.parse_frame(&unstuffed, protocol);
+                frame.raw_data = data.to_vec();
+                frame.unstuffed = Some(unstuffed);
+                frame
+            }
+            Err(e) => error_frame(protocol, data, &e),
+        }
+    }
+
+    /// 解析不含成帧编码的帧数据
+    fn parse_frame(&self, data: &[u8], protocol: &Protocol) -> ParsedFrame {
         // 验证帧头
         if let Some(ref header) = protocol.header {
             if data.len() < header.len() {
-                return ParsedFrame {
-                    protocol_name: protocol.name.clone(),
-                    raw_data: data.to_vec(),
-                    fields: Vec::new(),
-                    valid: false,
-                    error: Some("数据长度不足".to_string()),
-                };
+                return error_frame(protocol, data, "数据长度不足");
             }
             if &data[..header.len()] != header.as_slice() {
-                return ParsedFrame {
-                    protocol_name: protocol.name.clone(),
-                    raw_data: data.to_vec(),
-                    fields: Vec::new(),
-                    valid: false,
-                    error: Some("帧头不匹配".to_string()),
-                };
+                return error_frame(protocol, data, "帧头不匹配");
             }
         }
 
         // 验证帧尾
         if let Some(ref footer) = protocol.footer {
             if data.len() < footer.len() {
-                return ParsedFrame {
-                    protocol_name: protocol.name.clone(),
-                    raw_data: data.to_vec(),
-                    fields: Vec::new(),
-                    valid: false,
-                    error: Some("数据长度不足".to_string()),
-                };
+                return error_frame(protocol, data, "数据长度不足");
             }
             let footer_start = data.len() - footer.len();
             if &data[footer_start..] != footer.as_slice() {
-                return ParsedFrame {
-                    protocol_name: protocol.name.clone(),
-                    raw_data: data.to_vec(),
-                    fields: Vec::new(),
-                    valid: false,
-                    error: Some("帧尾不匹配".to_string()),
-                };
+                return error_frame(protocol, data, "帧尾不匹配");
             }
         }
 
+        // 验证校验值
+        let checksum = match verify_checksum(data, protocol) {
+            Some(Ok(result)) => Some(result),
+            Some(Err(e)) => return error_frame(protocol, data, &e),
+            None => None,
+        };
+
         // 解析字段
-        let mut fields = Vec::new();
         let header_len = protocol.header.as_ref().map(|h| h.len()).unwrap_or(0);
+        // 已解析的整数字段值，供长度与个数引用使用
+        let mut values: HashMap<&str, u64> = HashMap::new();
+        let layout = FieldLayout { protocol, data };
+        let mut cursor = FieldCursor::default();
+        let mut fields = layout.parse_fields_with(&protocol.fields, header_len, &mut values, 0, &mut cursor);
 
-        for field_def in &protocol.fields {
-            let abs_offset = header_len + field_def.offset;
-            
-            // 确定字段长度
-            let field_len = match field_def.field_type.size() {
-                Some(size) => size,
-                None => field_def.length.unwrap_or(1),
-            };
+        // 按判别字段选择消息布局，其字段接在公共字段之后解析
+        let has_variants = !protocol.variants.is_empty() || protocol.default_variant.is_some();
+        let discriminator = protocol.discriminator.as_ref()
+            .filter(|_| has_variants)
+            .map(|name| (name, values.get(name.as_str()).copied()));
+        let variant = match discriminator {
+            Some((_, Some(value))) => protocol.select_variant(value),
+            _ => None,
+        };
+        if let Some(variant) = variant {
+            fields.extend(layout.parse_fields_with(&variant.fields, header_len, &mut values, 0, &mut cursor));
+        }
+        let variant_error = match discriminator {
+            Some((name, None)) => Some(format!("判别字段 {} 无法取值，未选择消息布局", name)),
+            _ => None,
+        };
 
-            // 检查数据长度
-            if abs_offset + field_len > data.len() {
-                fields.push(ParsedField {
-                    name: field_def.name.clone(),
-                    field_type: field_def.field_type.clone(),
-                    raw_bytes: Vec::new(),
-                    value: "数据不足".to_string(),
-                    description: field_def.description.clone(),
-                });
-                continue;
+        // 长度字段与实际帧长不一致
+        let length_error = protocol.length_field.as_ref().and_then(|length_field| {
+            let value = *values.get(length_field.field.as_str())?;
+            match length_field.frame_length(value) {
+                Some(expected) if expected == data.len() => None,
+                Some(expected) => Some(format!("帧长度不符: 长度字段指示 {} 字节, 实际 {} 字节", expected, data.len())),
+                None => Some(format!("长度字段值无效: {}", value)),
             }
+        });
 
-            // 提取原始字节
-            let raw_bytes = data[abs_offset..abs_offset + field_len].to_vec();
-
-            // 解析值
-            let value = parse_field_value(&raw_bytes, &field_def.field_type, &field_def.byte_order);
-
-            fields.push(ParsedField {
-                name: field_def.name.clone(),
-                field_type: field_def.field_type.clone(),
-                raw_bytes,
-                value,
-                description: field_def.description.clone(),
-            });
-        }
+        // 校验不一致时仍给出字段，便于排查
+        let error = checksum.as_ref()
+            .filter(|c| !c.valid)
+            .map(|c| format!("校验失败: 期望 0x{:X}, 实际 0x{:X}", c.expected, c.actual))
+            .or(length_error)
+            .or(variant_error);
 
         ParsedFrame {
             protocol_name: protocol.name.clone(),
             raw_data: data.to_vec(),
             fields,
-            valid: true,
-            error: None,
+            valid: error.is_none(),
+            error,
+            checksum,
+            variant: variant.map(|v| v.name.clone()),
+            unstuffed: None,
+            ambiguous: Vec::new(),
+            modbus: None,
         }
     }
 }
 
-impl Default for ProtocolParser {
-    fn default() -> Self {
-        Self::new()
+/// 得分与最高分相差不超过此值的协议视为难以区分
+const AMBIGUITY_MARGIN: u32 = 1;
+
+/// 从候选结果中选出得分最高者，并按得分从高到低记录得分相同或接近的其他协议
+///
+/// 得分相同时取先出现者，调用方按协议名称排序以保证结果稳定。
+pub(super) fn best_match(mut candidates: Vec<(u32, ParsedFrame)>) -> Option<ParsedFrame> {
+    let best_score = candidates.iter().map(|(score, _)| *score).max()?;
+    let index = candidates.iter().position(|(score, _)| *score == best_score)?;
+    let (_, mut best) = candidates.remove(index);
+
+    let mut close: Vec<(u32, ParsedFrame)> = candidates.into_iter()
+        .filter(|(score, _)| score + AMBIGUITY_MARGIN >= best_score)
+        .collect();
+    close.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    best.ambiguous = close.into_iter().map(|(_, frame)| frame.protocol_name).collect();
+    Some(best)
+}
+
+/// 协议完整匹配时可能取得的最高分，计分规则与 `match_protocol` 一致
+pub(super) fn max_score(protocol: &Protocol) -> u32 {
+    if protocol.text.is_some() {
+        return protocol.fields.len() as u32;
+    }
+    if let Some(ref decoder) = protocol.decoder {
+        return decoder.checksum_type().size() as u32 * 2 + 2;
+    }
+
+    let header_len = protocol.header.as_ref().map(|h| h.len()).unwrap_or(0);
+    let footer_len = protocol.footer.as_ref().map(|f| f.len()).unwrap_or(0);
+    let checksum_len = protocol.checksum.as_ref().map(|c| c.size()).unwrap_or(0);
+
+    let mut score = header_len + footer_len + checksum_len * 2;
+    if protocol.framing.is_some() {
+        score += 1;
+    }
+    if protocol.length_field.is_some() {
+        score += 2;
+    } else if footer_len == 0 {
+        score += 1;
     }
+    score as u32
 }
 
-/// 解析字段值
-fn parse_field_value(bytes: &[u8], field_type: &FieldType, byte_order: &ByteOrder) -> String {
-    if bytes.is_empty() {
-        return "空".to_string();
+/// 字段或其子字段是否解码失败
+fn has_error(field: &ParsedField) -> bool {
+    matches!(field.value, FieldValue::Error(_)) || field.children.iter().any(has_error)
+}
+
+/// 构造解析失败的结果
+pub(super) fn error_frame(protocol: &Protocol, data: &[u8], error: &str) -> ParsedFrame {
+    unmatched_frame(&protocol.name, data, error)
+}
+
+/// 构造未能按协议解析的结果
+fn unmatched_frame(protocol_name: &str, data: &[u8], error: &str) -> ParsedFrame {
+    ParsedFrame {
+        protocol_name: protocol_name.to_string(),
+        raw_data: data.to_vec(),
+        fields: Vec::new(),
+        valid: false,
+        error: Some(error.to_string()),
+        checksum: None,
+        variant: None,
+        unstuffed: None,
+        ambiguous: Vec::new(),
+        modbus: None,
     }
+}
 
-    // 检查所需的最小长度
-    let required_len = field_type.size().unwrap_or(1);
-    if bytes.len() < required_len {
-        return format!("数据不足(需要{}字节)", required_len);
+/// 字段解析进度
+#[derive(Default)]
+pub(super) struct FieldCursor {
+    /// 变长字段的名义结束偏移及实际长度差
+    shifts: Vec<(usize, i64)>,
+    /// 实际结束偏移
+    pub(super) end: usize,
+}
+
+impl FieldCursor {
+    /// 按前面变长字段的长度差平移声明偏移
+    pub(super) fn place(&self, declared: usize) -> usize {
+        let shift: i64 = self.shifts.iter()
+            .filter(|(end, _)| *end <= declared)
+            .map(|(_, delta)| delta)
+            .sum();
+        (declared as i64 + shift).max(0) as usize
     }
 
-    match field_type {
-        FieldType::Uint8 => {
-            format!("{}", bytes[0])
-        }
-        FieldType::Int8 => {
-            format!("{}", bytes[0] as i8)
+    /// 记录字段实际占用，`offset` 为平移后的偏移
+    pub(super) fn advance(&mut self, declared: usize, offset: usize, nominal: usize, actual: usize) {
+        if actual != nominal {
+            self.shifts.push((declared + nominal, actual as i64 - nominal as i64));
         }
-        FieldType::Uint16 => {
-            let value = match byte_order {
-                ByteOrder::BigEndian => u16::from_be_bytes([bytes[0], bytes[1]]),
-                ByteOrder::LittleEndian => u16::from_le_bytes([bytes[0], bytes[1]]),
+        self.end = self.end.max(offset + actual);
+    }
+}
+
+/// 字段布局解析
+struct FieldLayout<'a> {
+    protocol: &'a Protocol,
+    data: &'a [u8],
+}
+
+impl<'a> FieldLayout<'a> {
+    /// 解析字段列表，偏移相对 `base`；返回解析结果及实际结束偏移（相对 `base`）
+    ///
+    /// 变长字段（长度或个数来自其他字段、含变长内容的子结构）的实际长度与名义长度不同时，
+    /// 偏移位于其名义范围之后的字段随之整体平移。
+    fn parse_fields(
+        &self,
+        defs: &'a [ProtocolField],
+        base: usize,
+        values: &mut HashMap<&'a str, u64>,
+        depth: usize,
+    ) -> (Vec<ParsedField>, usize) {
+        let mut cursor = FieldCursor::default();
+        let fields = self.parse_fields_with(defs, base, values, depth, &mut cursor);
+        (fields, cursor.end)
+    }
+
+    /// 在已有解析进度上继续解析字段列表
+    fn parse_fields_with(
+        &self,
+        defs: &'a [ProtocolField],
+        base: usize,
+        values: &mut HashMap<&'a str, u64>,
+        depth: usize,
+        cursor: &mut FieldCursor,
+    ) -> Vec<ParsedField> {
+        let mut fields = Vec::new();
+
+        for field_def in defs {
+            let offset = cursor.place(field_def.offset);
+
+            let (field, actual_len) = if field_def.is_array() || field_def.field_type == FieldType::Struct {
+                self.parse_compound(field_def, base + offset, values, depth)
+            } else {
+                self.parse_primitive(field_def, field_def.name.clone(), base + offset, values)
             };
-            format!("{}", value)
+
+            let nominal = self.protocol.nominal_size(field_def);
+            cursor.advance(field_def.offset, offset, nominal, actual_len.unwrap_or(nominal));
+            fields.push(field);
         }
-        FieldType::Int16 => {
-            let value = match byte_order {
-                ByteOrder::BigEndian => i16::from_be_bytes([bytes[0], bytes[1]]),
-                ByteOrder::LittleEndian => i16::from_le_bytes([bytes[0], bytes[1]]),
-            };
-            format!("{}", value)
+
+        fields
+    }
+
+    /// 解析基本类型字段，返回解析结果及实际长度（失败时为 `None`）
+    fn parse_primitive(
+        &self,
+        field_def: &'a ProtocolField,
+        name: String,
+        start: usize,
+        values: &mut HashMap<&'a str, u64>,
+    ) -> (ParsedField, Option<usize>) {
+        let field_len = if field_def.is_terminated() {
+            // 长度含结束符
+            match self.data.get(start..).and_then(|rest| rest.iter().position(|b| *b == 0)) {
+                Some(pos) => pos + 1,
+                None => {
+                    let error = FieldError::new(FieldErrorKind::InsufficientData, "未找到结束符 0x00");
+                    return (failed_field(field_def, name, error), None);
+                }
+            }
+        } else {
+            match field_length(field_def, values) {
+                Ok(len) => len,
+                Err(e) => return (failed_field(field_def, name, e), None),
+            }
+        };
+
+        if start + field_len > self.data.len() {
+            let available = self.data.len().saturating_sub(start);
+            return (failed_field(field_def, name, insufficient_data(field_len, available)), None);
         }
-        FieldType::Uint32 => {
-            let value = match byte_order {
-                ByteOrder::BigEndian => u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
-                ByteOrder::LittleEndian => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
-            };
-            format!("{}", value)
+
+        let raw_bytes = self.data[start..start + field_len].to_vec();
+        let decoded = parse_field_value(&raw_bytes, field_def);
+        if let Some(integer) = decoded.integer {
+            values.insert(field_def.name.as_str(), integer);
         }
-        FieldType::Int32 => {
-            let value = match byte_order {
-                ByteOrder::BigEndian => i32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
-                ByteOrder::LittleEndian => i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
-            };
-            format!("{}", value)
+
+        (decoded_field(field_def, name, raw_bytes, decoded), Some(field_len))
+    }
+
+    /// 解析数组或子结构字段
+    fn parse_compound(
+        &self,
+        field_def: &'a ProtocolField,
+        start: usize,
+        values: &mut HashMap<&'a str, u64>,
+        depth: usize,
+    ) -> (ParsedField, Option<usize>) {
+        let name = field_def.name.clone();
+
+        let structure = match field_def.field_type {
+            FieldType::Struct => {
+                let struct_name = field_def.struct_name.as_deref().unwrap_or_default();
+                match self.protocol.find_struct(struct_name) {
+                    Some(structure) => Some(structure),
+                    None => {
+                        let error = FieldError::new(
+                            FieldErrorKind::UnresolvedReference,
+                            format!("子结构不存在: {}", struct_name),
+                        );
+                        return (failed_field(field_def, name, error), None);
+                    }
+                }
+            }
+            _ => None,
+        };
+        if structure.is_some() && depth >= MAX_STRUCT_DEPTH {
+            let error = FieldError::new(FieldErrorKind::NestingTooDeep, "子结构嵌套过深");
+            return (failed_field(field_def, name, error), None);
         }
-        FieldType::Uint64 => {
-            let value = match byte_order {
-                ByteOrder::BigEndian => u64::from_be_bytes([
-                    bytes[0], bytes[1], bytes[2], bytes[3],
-                    bytes[4], bytes[5], bytes[6], bytes[7],
-                ]),
-                ByteOrder::LittleEndian => u64::from_le_bytes([
-                    bytes[0], bytes[1], bytes[2], bytes[3],
-                    bytes[4], bytes[5], bytes[6], bytes[7],
-                ]),
+
+        let count = match field_def.count_from {
+            Some(ref count_from) => match resolve_reference(count_from, values) {
+                Ok(count) => count,
+                Err(e) => return (failed_field(field_def, name, e), None),
+            },
+            None => field_def.count.unwrap_or(1),
+        };
+
+        let mut children = Vec::new();
+        let mut pos = start;
+        for index in 0..count {
+            let element_name = if field_def.is_array() {
+                format!("{}[{}]", field_def.name, index)
+            } else {
+                field_def.name.clone()
             };
-            format!("{}", value)
-        }
-        FieldType::Int64 => {
-            let value = match byte_order {
-                ByteOrder::BigEndian => i64::from_be_bytes([
-                    bytes[0], bytes[1], bytes[2], bytes[3],
-                    bytes[4], bytes[5], bytes[6], bytes[7],
-                ]),
-                ByteOrder::LittleEndian => i64::from_le_bytes([
-                    bytes[0], bytes[1], bytes[2], bytes[3],
-                    bytes[4], bytes[5], bytes[6], bytes[7],
-                ]),
+
+            if pos >= self.data.len() {
+                let error = insufficient_data(1, 0);
+                children.push(failed_field(field_def, element_name, error));
+                break;
+            }
+
+            let (element, element_len) = match structure {
+                Some(structure) => {
+                    // 数组元素各自拥有字段值作用域，单个子结构的字段值对后续字段可见
+                    let (fields, len) = if field_def.is_array() {
+                        let mut scoped = values.clone();
+                        self.parse_fields(&structure.fields, pos, &mut scoped, depth + 1)
+                    } else {
+                        self.parse_fields(&structure.fields, pos, values, depth + 1)
+                    };
+                    let element = self.container_field(field_def, element_name, pos, len, structure.name.clone(), fields);
+                    (element, Some(len))
+                }
+                None => self.parse_primitive(field_def, element_name, pos, values),
             };
-            format!("{}", value)
+
+            children.push(element);
+            match element_len {
+                Some(len) if len > 0 => pos += len,
+                _ => break,
+            }
         }
-        FieldType::Float32 => {
-            let value = match byte_order {
-                ByteOrder::BigEndian => f32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
-                ByteOrder::LittleEndian => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
-            };
-            format!("{:.6}", value)
+
+        if !field_def.is_array() {
+            if let Some(field) = children.pop() {
+                let len = field.raw_bytes.len();
+                return (field, Some(len));
+            }
+        }
+
+        let display = format!("[{} 项]", children.len());
+        let field = self.container_field(field_def, name, start, pos - start, display, children);
+        (field, Some(pos - start))
+    }
+
+    /// 构造包含子字段的字段
+    fn container_field(
+        &self,
+        field_def: &ProtocolField,
+        name: String,
+        start: usize,
+        len: usize,
+        display: String,
+        children: Vec<ParsedField>,
+    ) -> ParsedField {
+        let end = (start + len).min(self.data.len());
+        let raw_bytes = self.data[start.min(end)..end].to_vec();
+        ParsedField {
+            name,
+            field_type: field_def.field_type.clone(),
+            value: FieldValue::Bytes(raw_bytes.clone()),
+            raw_bytes,
+            display: Some(display),
+            raw_value: None,
+            scaled_value: None,
+            unit: None,
+            description: field_def.description.clone(),
+            children,
         }
-        FieldType::Float64 => {
-            let value = match byte_order {
-                ByteOrder::BigEndian => f64::from_be_bytes([
-                    bytes[0], bytes[1], bytes[2], bytes[3],
-                    bytes[4], bytes[5], bytes[6], bytes[7],
-                ]),
-                ByteOrder::LittleEndian => f64::from_le_bytes([
-                    bytes[0], bytes[1], bytes[2], bytes[3],
-                    bytes[4], bytes[5], bytes[6], bytes[7],
-                ]),
+    }
+}
+
+/// 构造解码失败的字段
+fn failed_field(field_def: &ProtocolField, name: String, error: FieldError) -> ParsedField {
+    ParsedField {
+        name,
+        field_type: field_def.field_type.clone(),
+        raw_bytes: Vec::new(),
+        value: FieldValue::Error(error),
+        display: None,
+        raw_value: None,
+        scaled_value: None,
+        unit: field_def.unit.clone(),
+        description: field_def.description.clone(),
+        children: Vec::new(),
+    }
+}
+
+fn insufficient_data(required: usize, available: usize) -> FieldError {
+    FieldError::new(
+        FieldErrorKind::InsufficientData,
+        format!("数据不足: 需要 {} 字节, 实际 {} 字节", required, available),
+    )
+}
+
+/// 从已解析的字段值计算长度或个数
+fn resolve_reference(reference: &LengthRef, values: &HashMap<&str, u64>) -> Result<usize, FieldError> {
+    let value = values.get(reference.field.as_str()).ok_or_else(|| {
+        FieldError::new(
+            FieldErrorKind::UnresolvedReference,
+            format!("引用的字段不存在: {}", reference.field),
+        )
+    })?;
+    reference.resolve(*value).ok_or_else(|| {
+        FieldError::new(FieldErrorKind::InvalidLength, format!("长度无效: {}", value))
+    })
+}
+
+/// 计算字段实际长度，变长字段从已解析的字段值中取长度
+fn field_length(field_def: &ProtocolField, values: &HashMap<&str, u64>) -> Result<usize, FieldError> {
+    if let Some(size) = field_def.field_type.size() {
+        return Ok(size);
+    }
+    match field_def.length_from {
+        Some(ref length_from) => resolve_reference(length_from, values),
+        None => Ok(field_def.length.unwrap_or(1)),
+    }
+}
+
+/// 从整数容器中取出位字段
+fn parse_bit_field(bytes: &[u8], field_def: &ProtocolField, bits: &BitRange) -> Result<u64, FieldError> {
+    if !field_def.field_type.is_integer() {
+        return Err(FieldError::new(FieldErrorKind::UnsupportedType, "位字段须使用整数类型"));
+    }
+    let container = read_uint(bytes, &field_def.byte_order);
+    bits.extract(container, bytes.len() as u32 * 8)
+        .map_err(|e| FieldError::new(FieldErrorKind::InvalidBitRange, e))
+}
+
+impl Default for ProtocolParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 字段取值结果
+pub(super) struct DecodedValue {
+    /// 字段值
+    value: FieldValue,
+    /// 显示文本
+    display: Option<String>,
+    /// 整数原始值，供长度引用使用
+    integer: Option<u64>,
+    /// 原始数值
+    raw_value: Option<f64>,
+    /// 工程值
+    scaled_value: Option<f64>,
+}
+
+/// 由取值结果构造解析后的字段
+pub(super) fn decoded_field(field_def: &ProtocolField, name: String, raw_bytes: Vec<u8>, decoded: DecodedValue) -> ParsedField {
+    ParsedField {
+        name,
+        field_type: field_def.field_type.clone(),
+        raw_bytes,
+        value: decoded.value,
+        display: decoded.display,
+        raw_value: decoded.raw_value,
+        scaled_value: decoded.scaled_value,
+        unit: field_def.unit.clone(),
+        description: field_def.description.clone(),
+        children: Vec::new(),
+    }
+}
+
+/// 解析字段值
+fn parse_field_value(bytes: &[u8], field_def: &ProtocolField) -> DecodedValue {
+    let decoded = match field_def.bits {
+        Some(ref bits) => parse_bit_field(bytes, field_def, bits).map(|bit_value| {
+            let value = if bits.is_flag() {
+                FieldValue::Bool(bit_value == 1)
+            } else {
+                FieldValue::Unsigned(bit_value)
+            };
+            (value, Some(bit_value))
+        }),
+        None => decode_raw_value(bytes, &field_def.field_type, &field_def.byte_order).map(|value| {
+            let integer = match value {
+                _ if field_def.field_type.is_integer() => Some(read_uint(bytes, &field_def.byte_order)),
+                FieldValue::Unsigned(v) if field_def.field_type.is_encoded_integer() => Some(v),
+                _ => None,
+            };
+            (value, integer)
+        }),
+    };
+
+    // 位字段按单字节宽度显示
+    let width = if field_def.bits.is_some() { 2 } else { bytes.len() * 2 };
+    describe_value(field_def, decoded, width)
+}
+
+/// 由解码结果生成字段值与显示文本，`width` 为枚举值显示的十六进制位数
+///
+/// 取值映射命中时给出枚举值，显示为 "ACK (0x06)"；否则按比例、偏移与单位显示工程值。
+pub(super) fn describe_value(
+    field_def: &ProtocolField,
+    decoded: Result<(FieldValue, Option<u64>), FieldError>,
+    width: usize,
+) -> DecodedValue {
+    let (value, integer) = match decoded {
+        Ok(decoded) => decoded,
+        Err(e) => {
+            return DecodedValue {
+                value: FieldValue::Error(e),
+                display: None,
+                integer: None,
+                raw_value: None,
+                scaled_value: None,
             };
-            format!("{:.10}", value)
         }
-        FieldType::String => {
-            String::from_utf8_lossy(bytes).to_string()
+    };
+
+    let raw_value = value.as_f64();
+    let scaled_value = raw_value
+        .filter(|_| field_def.has_scaling())
+        .map(|raw| field_def.apply_scaling(raw));
+
+    let label = field_def.value_map.as_ref()
+        .zip(integer)
+        .and_then(|(value_map, integer)| value_map.label(integer));
+
+    let (value, display) = match (label, integer) {
+        (Some(label), Some(integer)) => {
+            let display = format!("{} (0x{:0width$X})", label, integer, width = width);
+            (FieldValue::Enum { value: integer, label: label.to_string() }, display)
         }
-        FieldType::Bytes => {
-            bytes.iter()
-                .map(|b| format!("{:02X}", b))
-                .collect::<Vec<_>>()
-                .join(" ")
+        _ => {
+            let display = match scaled_value {
+                Some(scaled) => field_def.format_scaled(scaled),
+                None => format_value(&value, &field_def.field_type),
+            };
+            (value, display)
         }
-        FieldType::Hex => {
-            bytes.iter()
-                .map(|b| format!("{:02X}", b))
-                .collect::<Vec<_>>()
-                .join(" ")
+    };
+
+    DecodedValue {
+        value,
+        display: Some(display),
+        integer,
+        raw_value,
+        scaled_value,
+    }
+}
+
+/// 按字段类型解码原始字节
+fn decode_raw_value(bytes: &[u8], field_type: &FieldType, byte_order: &ByteOrder) -> Result<FieldValue, FieldError> {
+    let required = field_type.size().unwrap_or(0);
+    if bytes.len() < required {
+        return Err(insufficient_data(required, bytes.len()));
+    }
+
+    let unsigned = read_uint(&bytes[..required], byte_order);
+    let invalid = |e: String| FieldError::new(FieldErrorKind::InvalidEncoding, e);
+    let value = match field_type {
+        FieldType::Uint8 | FieldType::Uint16 | FieldType::Uint32 | FieldType::Uint64 => FieldValue::Unsigned(unsigned),
+        FieldType::Int8 => FieldValue::Signed(unsigned as u8 as i8 as i64),
+        FieldType::Int16 => FieldValue::Signed(unsigned as u16 as i16 as i64),
+        FieldType::Int32 => FieldValue::Signed(unsigned as u32 as i32 as i64),
+        FieldType::Int64 => FieldValue::Signed(unsigned as i64),
+        FieldType::Float16 => FieldValue::Float(half_to_f64(unsigned as u16)),
+        FieldType::Float32 => FieldValue::Float(f32::from_bits(unsigned as u32) as f64),
+        FieldType::Float64 => FieldValue::Float(f64::from_bits(unsigned)),
+        FieldType::String => FieldValue::String(String::from_utf8_lossy(bytes).to_string()),
+        FieldType::CString => {
+            let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+            FieldValue::String(String::from_utf8_lossy(&bytes[..end]).to_string())
         }
+        FieldType::Bcd => FieldValue::Unsigned(decode_bcd(bytes, byte_order).map_err(invalid)?),
+        FieldType::AsciiHex => FieldValue::Unsigned(decode_ascii_hex(bytes).map_err(invalid)?),
+        FieldType::Bool => FieldValue::Bool(unsigned != 0),
+        FieldType::Timestamp => FieldValue::Timestamp(unsigned as i64 * 1000),
+        FieldType::TimestampMs => FieldValue::Timestamp(unsigned as i64),
+        FieldType::Bytes | FieldType::Hex | FieldType::Struct => FieldValue::Bytes(bytes.to_vec()),
+    };
+    Ok(value)
+}
+
+/// 格式化字段值
+fn format_value(value: &FieldValue, field_type: &FieldType) -> String {
+    match value {
+        FieldValue::Unsigned(v) => v.to_string(),
+        FieldValue::Signed(v) => v.to_string(),
+        FieldValue::Float(v) if *field_type == FieldType::Float16 => format!("{:.4}", v),
+        FieldValue::Float(v) if *field_type == FieldType::Float32 => format!("{:.6}", v),
+        FieldValue::Float(v) => format!("{:.10}", v),
+        FieldValue::Bool(v) => v.to_string(),
+        FieldValue::String(s) => s.clone(),
+        FieldValue::Timestamp(v) => format_timestamp(*v, *field_type == FieldType::TimestampMs),
+        FieldValue::Bytes(bytes) => bytes.iter()
+            .map(|b| format!("{:02X}", b))
+            .collect::<Vec<_>>()
+            .join(" "),
+        FieldValue::Enum { value, label } => format!("{} (0x{:X})", label, value),
+        FieldValue::Error(e) => e.message.clone(),
     }
 }
 
 /// 解析器状态
 pub struct ParserState {
     pub parser: Mutex<ProtocolParser>,
+    /// 内置 Modbus 解码器的请求与响应配对
+    pub modbus: Mutex<ModbusTracker>,
 }
 
 impl ParserState {
     pub fn new() -> Self {
         Self {
             parser: Mutex::new(ProtocolParser::new()),
+            modbus: Mutex::new(ModbusTracker::default()),
         }
     }
 }
@@ -287,3 +831,50 @@ impl Default for ParserState {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_discriminator_value_is_reported() {
+        let mut protocol = Protocol::new("命令");
+        protocol.fields = vec![ProtocolField::new("cmd", FieldType::Uint8, 0)];
+        protocol.discriminator = Some("cmd".to_string());
+        protocol.default_variant = Some(ProtocolVariant {
+            name: "default".to_string(),
+            values: Vec::new(),
+            fields: vec![ProtocolField::new("a", FieldType::Uint8, 1)],
+        });
+
+        let frame = ProtocolParser::new().parse_with_protocol(&[], &protocol);
+        assert!(!frame.valid);
+        assert!(frame.error.unwrap().contains("判别字段 cmd"));
+    }
+
+    #[test]
+    fn detect_reports_close_scores_as_ambiguous() {
+        let mut tagged = Protocol::new("带帧头").with_header(vec![0xAA]);
+        tagged.fields = vec![ProtocolField::new("value", FieldType::Uint8, 0)];
+        let mut plain = Protocol::new("无帧头");
+        plain.fields = vec![ProtocolField::new("value", FieldType::Uint16, 0)];
+        let mut framed = Protocol::new("长帧头").with_header(vec![0xAA, 0x01, 0x02]);
+        framed.fields = vec![ProtocolField::new("value", FieldType::Uint8, 0)];
+
+        let mut parser = ProtocolParser::new();
+        parser.register_protocol(plain);
+        parser.register_protocol(tagged);
+        parser.register_protocol(framed);
+
+        // 带帧头得 2 分，无帧头得 1 分，长帧头不匹配
+        let frame = parser.detect(&[0xAA, 0x01]).unwrap();
+        assert_eq!(frame.protocol_name, "带帧头");
+        assert_eq!(frame.ambiguous, ["无帧头"]);
+
+        // 长帧头得 4 分，其余协议长度不符或得分相差过大
+        let frame = parser.detect(&[0xAA, 0x01, 0x02, 0x03]).unwrap();
+        assert_eq!(frame.protocol_name, "长帧头");
+        assert!(frame.ambiguous.is_empty());
+    }
+}
+
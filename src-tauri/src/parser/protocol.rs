@@ -1,4 +1,9 @@
 use serde::{Deserialize, Serialize};
+use super::checksum::read_uint;
+use super::crc::{hex_u64, hex_u64_vec, CrcSpec};
+use super::framing::Framing;
+use super::modbus::{ModbusMessage, ModbusMode};
+use super::text::TextFormat;
 
 /// 字段类型
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -15,27 +20,67 @@ pub enum FieldType {
     Int32,
     Int64,
     /// 浮点数
+    Float16,
     Float32,
     Float64,
     /// 字符串
     String,
+    /// 以 0x00 结尾的字符串，未设置长度时长度由结束符决定
+    CString,
     /// 字节数组
     Bytes,
     /// 十六进制
     Hex,
+    /// 压缩 BCD 码，每字节两位十进制数
+    Bcd,
+    /// 以 ASCII 十六进制字符表示的无符号整数
+    AsciiHex,
+    /// 布尔，非 0 为真
+    Bool,
+    /// Unix 时间戳（秒，4 字节）
+    Timestamp,
+    /// Unix 时间戳（毫秒，8 字节）
+    TimestampMs,
+    /// 子结构，由 `struct_name` 指定
+    Struct,
 }
 
 impl FieldType {
     /// 获取字段类型的字节大小
     pub fn size(&self) -> Option<usize> {
         match self {
-            FieldType::Uint8 | FieldType::Int8 => Some(1),
-            FieldType::Uint16 | FieldType::Int16 => Some(2),
-            FieldType::Uint32 | FieldType::Int32 | FieldType::Float32 => Some(4),
-            FieldType::Uint64 | FieldType::Int64 | FieldType::Float64 => Some(8),
-            FieldType::String | FieldType::Bytes | FieldType::Hex => None,
+            FieldType::Uint8 | FieldType::Int8 | FieldType::Bool => Some(1),
+            FieldType::Uint16 | FieldType::Int16 | FieldType::Float16 => Some(2),
+            FieldType::Uint32 | FieldType::Int32 | FieldType::Float32 | FieldType::Timestamp => Some(4),
+            FieldType::Uint64 | FieldType::Int64 | FieldType::Float64 | FieldType::TimestampMs => Some(8),
+            FieldType::String | FieldType::CString | FieldType::Bytes | FieldType::Hex
+                | FieldType::Bcd | FieldType::AsciiHex | FieldType::Struct => None,
         }
     }
+
+    /// 是否为以文本或 BCD 表示的整数类型
+    pub fn is_encoded_integer(&self) -> bool {
+        matches!(self, FieldType::Bcd | FieldType::AsciiHex)
+    }
+
+    /// 是否解码为整数值（可被长度引用、取值映射与判别字段使用）
+    pub fn is_integral(&self) -> bool {
+        self.is_integer() || self.is_encoded_integer()
+    }
+
+    /// 是否须由 `length` 或 `length_from` 指定长度
+    pub fn requires_length(&self) -> bool {
+        matches!(self, FieldType::String | FieldType::Bytes | FieldType::Hex) || self.is_encoded_integer()
+    }
+
+    /// 是否为整数类型
+    pub fn is_integer(&self) -> bool {
+        matches!(
+            self,
+            FieldType::Uint8 | FieldType::Uint16 | FieldType::Uint32 | FieldType::Uint64
+                | FieldType::Int8 | FieldType::Int16 | FieldType::Int32 | FieldType::Int64
+        )
+    }
 }
 
 /// 字节序
@@ -66,6 +111,39 @@ pub struct ProtocolField {
     pub offset: usize,
     /// 字段长度（用于 String, Bytes, Hex 类型）
     pub length: Option<usize>,
+    /// 由前面某个字段的值决定长度（用于 String, Bytes, Hex 类型）
+    ///
+    /// 此时 `length` 为声明时的名义长度（缺省为 0），偏移位于该字段名义范围之后的字段
+    /// 会随实际长度整体后移。
+    #[serde(default)]
+    pub length_from: Option<LengthRef>,
+    /// 位字段：在整数类型容器内按位取值，宽度为 1 时作为布尔标志
+    #[serde(default)]
+    pub bits: Option<BitRange>,
+    /// 固定元素个数，设置后字段为数组
+    #[serde(default)]
+    pub count: Option<usize>,
+    /// 由前面某个字段的值决定元素个数，声明时按 0 长度占位
+    #[serde(default)]
+    pub count_from: Option<LengthRef>,
+    /// 子结构名称（字段类型为 Struct 时使用）
+    #[serde(default)]
+    pub struct_name: Option<String>,
+    /// 取值到名称的映射（用于整数及位字段）
+    #[serde(default)]
+    pub value_map: Option<ValueMap>,
+    /// 比例系数：工程值 = 原始值 × scale + value_offset
+    #[serde(default)]
+    pub scale: Option<f64>,
+    /// 偏移量
+    #[serde(default)]
+    pub value_offset: Option<f64>,
+    /// 单位
+    #[serde(default)]
+    pub unit: Option<String>,
+    /// 显示小数位数，为空时按 scale 与 value_offset 的小数位数推断
+    #[serde(default)]
+    pub precision: Option<u8>,
     /// 字节序
     pub byte_order: ByteOrder,
     /// 描述
@@ -82,6 +160,16 @@ impl ProtocolField {
             field_type,
             offset,
             length: None,
+            length_from: None,
+            bits: None,
+            count: None,
+            count_from: None,
+            struct_name: None,
+            value_map: None,
+            scale: None,
+            value_offset: None,
+            unit: None,
+            precision: None,
             byte_order: ByteOrder::default(),
             description: None,
             visible: true,
@@ -94,6 +182,51 @@ impl ProtocolField {
         self
     }
 
+    #[allow(dead_code)]
+    pub fn with_length_from(mut self, length_from: LengthRef) -> Self {
+        self.length_from = Some(length_from);
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn with_bits(mut self, bits: BitRange) -> Self {
+        self.bits = Some(bits);
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn with_count(mut self, count: usize) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn with_count_from(mut self, count_from: LengthRef) -> Self {
+        self.count_from = Some(count_from);
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn with_struct(mut self, struct_name: &str) -> Self {
+        self.field_type = FieldType::Struct;
+        self.struct_name = Some(struct_name.to_string());
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn with_value_map(mut self, value_map: ValueMap) -> Self {
+        self.value_map = Some(value_map);
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn with_scaling(mut self, scale: f64, value_offset: f64, unit: &str) -> Self {
+        self.scale = Some(scale);
+        self.value_offset = Some(value_offset);
+        self.unit = Some(unit.to_string()).filter(|u| !u.is_empty());
+        self
+    }
+
     #[allow(dead_code)]
     pub fn with_byte_order(mut self, order: ByteOrder) -> Self {
         self.byte_order = order;
@@ -105,8 +238,180 @@ impl ProtocolField {
         self.description = Some(description.to_string());
         self
     }
+
+    /// 是否为以结束符确定长度的字符串
+    pub fn is_terminated(&self) -> bool {
+        self.field_type == FieldType::CString && self.length.is_none() && self.length_from.is_none()
+    }
+
+    /// 是否为数组
+    pub fn is_array(&self) -> bool {
+        self.count.is_some() || self.count_from.is_some()
+    }
+
+    /// 是否配置了工程量换算或单位
+    pub fn has_scaling(&self) -> bool {
+        self.scale.is_some() || self.value_offset.is_some() || self.unit.is_some() || self.precision.is_some()
+    }
+
+    /// 原始值换算为工程值
+    pub fn apply_scaling(&self, raw: f64) -> f64 {
+        raw * self.scale.unwrap_or(1.0) + self.value_offset.unwrap_or(0.0)
+    }
+
+    /// 工程值还原为原始值，比例系数为 0 时返回 `None`
+    pub fn unapply_scaling(&self, value: f64) -> Option<f64> {
+        let scale = self.scale.unwrap_or(1.0);
+        if scale == 0.0 {
+            return None;
+        }
+        Some((value - self.value_offset.unwrap_or(0.0)) / scale)
+    }
+
+    /// 按精度与单位格式化工程值
+    pub fn format_scaled(&self, value: f64) -> String {
+        let precision = self.precision.map(|p| p as usize).unwrap_or_else(|| {
+            decimals(self.scale.unwrap_or(1.0)).max(decimals(self.value_offset.unwrap_or(0.0)))
+        });
+        match self.unit {
+            Some(ref unit) => format!("{:.*} {}", precision, value, unit),
+            None => format!("{:.*}", precision, value),
+        }
+    }
+}
+
+/// 数值最短十进制表示中的小数位数
+fn decimals(value: f64) -> usize {
+    let text = value.to_string();
+    text.split_once('.').map(|(_, frac)| frac.len()).unwrap_or(0)
+}
+
+/// 位编号方式
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum BitOrder {
+    /// 第 0 位为最低位
+    #[default]
+    LsbFirst,
+    /// 第 0 位为最高位
+    MsbFirst,
+}
+
+/// 位字段范围
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BitRange {
+    /// 起始位
+    pub offset: u32,
+    /// 位宽
+    pub width: u32,
+    /// 位编号方式
+    #[serde(default)]
+    pub order: BitOrder,
 }
 
+impl BitRange {
+    /// 位宽为 1 时视为标志位
+    pub fn is_flag(&self) -> bool {
+        self.width == 1
+    }
+
+    /// 从容器值中取出位字段，`container_bits` 为容器位数
+    pub fn extract(&self, container: u64, container_bits: u32) -> Result<u64, String> {
+        let (shift, mask) = self.shift_mask(container_bits)?;
+        Ok((container >> shift) & mask)
+    }
+
+    /// 将位字段写入容器值，取值超出位宽时返回错误
+    pub fn insert(&self, container: u64, value: u64, container_bits: u32) -> Result<u64, String> {
+        let (shift, mask) = self.shift_mask(container_bits)?;
+        if value > mask {
+            return Err(format!("取值 {} 超出 {} 位位宽", value, self.width));
+        }
+        Ok((container & !(mask << shift)) | (value << shift))
+    }
+
+    /// 位字段在容器中的移位量与掩码
+    fn shift_mask(&self, container_bits: u32) -> Result<(u32, u64), String> {
+        if self.width == 0 || self.offset.checked_add(self.width).is_none_or(|end| end > container_bits) {
+            return Err(format!(
+                "位范围超出容器: 第 {} 位起 {} 位, 容器 {} 位",
+                self.offset, self.width, container_bits
+            ));
+        }
+        let shift = match self.order {
+            BitOrder::LsbFirst => self.offset,
+            BitOrder::MsbFirst => container_bits - self.offset - self.width,
+        };
+        let mask = if self.width >= 64 { u64::MAX } else { (1u64 << self.width) - 1 };
+        Ok((shift, mask))
+    }
+}
+
+/// 取值名称
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueLabel {
+    /// 原始值
+    #[serde(with = "hex_u64")]
+    pub value: u64,
+    /// 名称
+    pub label: String,
+}
+
+/// 取值映射
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueMap {
+    /// 映射条目
+    #[serde(default)]
+    pub entries: Vec<ValueLabel>,
+    /// 未匹配时使用的名称，为空时直接显示数值
+    #[serde(default)]
+    pub fallback: Option<String>,
+}
+
+impl ValueMap {
+    /// 查找取值对应的名称，未匹配时返回 fallback
+    pub fn label(&self, value: u64) -> Option<&str> {
+        self.entries.iter()
+            .find(|e| e.value == value)
+            .map(|e| e.label.as_str())
+            .or(self.fallback.as_deref())
+    }
+}
+
+/// 可复用的子结构，字段偏移相对结构起始
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolStruct {
+    /// 结构名称
+    pub name: String,
+    /// 描述
+    pub description: Option<String>,
+    /// 字段列表
+    pub fields: Vec<ProtocolField>,
+}
+
+/// 消息布局，判别字段取值命中 `values` 时使用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolVariant {
+    /// 布局名称
+    pub name: String,
+    /// 匹配的判别值
+    #[serde(default, with = "hex_u64_vec")]
+    pub values: Vec<u64>,
+    /// 布局字段，偏移与公共字段相同（相对帧头之后）
+    pub fields: Vec<ProtocolField>,
+}
+
+/// 子结构最大嵌套深度
+pub const MAX_STRUCT_DEPTH: usize = 8;
+
+/// 单帧最大长度，字段不能超出该范围，流式分帧超过后视为同步丢失
+pub const MAX_FRAME_LENGTH: usize = 4096;
+
 /// 协议定义
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -123,8 +428,35 @@ pub struct Protocol {
     pub footer: Option<Vec<u8>>,
     /// 字段列表
     pub fields: Vec<ProtocolField>,
+    /// 子结构定义
+    #[serde(default)]
+    pub structs: Vec<ProtocolStruct>,
+    /// 判别字段名称（须为公共字段中的整数或位字段）
+    #[serde(default)]
+    pub discriminator: Option<String>,
+    /// 按判别值区分的消息布局
+    #[serde(default)]
+    pub variants: Vec<ProtocolVariant>,
+    /// 判别值未命中时使用的布局
+    #[serde(default)]
+    pub default_variant: Option<ProtocolVariant>,
     /// 校验类型
     pub checksum: Option<ChecksumType>,
+    /// 校验范围与位置，为空时使用默认布局
+    #[serde(default)]
+    pub checksum_layout: Option<ChecksumLayout>,
+    /// 帧长度字段，用于流式分帧
+    #[serde(default)]
+    pub length_field: Option<LengthField>,
+    /// 成帧编码，设置后按定界字节分帧并在解析前解除转义
+    #[serde(default)]
+    pub framing: Option<Framing>,
+    /// 文本协议格式，设置后按行匹配文本，二进制布局、校验与成帧编码不起作用
+    #[serde(default)]
+    pub text: Option<TextFormat>,
+    /// 内置解码器，设置后由解码器给出字段，字段定义与帧格式设置不起作用
+    #[serde(default)]
+    pub decoder: Option<BuiltinDecoder>,
     /// 创建时间
     pub created_at: i64,
     /// 更新时间
@@ -142,7 +474,16 @@ impl Protocol {
             header: None,
             footer: None,
             fields: Vec::new(),
+            structs: Vec::new(),
+            discriminator: None,
+            variants: Vec::new(),
+            default_variant: None,
             checksum: None,
+            checksum_layout: None,
+            length_field: None,
+            framing: None,
+            text: None,
+            decoder: None,
             created_at: now,
             updated_at: now,
         }
@@ -163,18 +504,155 @@ impl Protocol {
         self.updated_at = chrono::Utc::now().timestamp_millis();
     }
 
-    /// 获取协议最小帧长度
+    /// 获取协议最小帧长度（仅计公共字段）
     pub fn min_frame_length(&self) -> usize {
+        self.variant_frame_length(None)
+    }
+
+    /// 使用指定消息布局时的帧长度，取公共字段与布局字段中结束最晚者
+    pub fn variant_frame_length(&self, variant: Option<&ProtocolVariant>) -> usize {
         let header_len = self.header.as_ref().map(|h| h.len()).unwrap_or(0);
         let footer_len = self.footer.as_ref().map(|f| f.len()).unwrap_or(0);
         let checksum_len = self.checksum.as_ref().map(|c| c.size()).unwrap_or(0);
 
-        let fields_end = self.fields.iter().map(|f| {
-            let size = f.field_type.size().or(f.length).unwrap_or(1);
-            f.offset + size
-        }).max().unwrap_or(0);
+        let extent = self.fields_extent(&self.fields, 0);
+        let extent = variant.map_or(extent, |v| extent.max(self.fields_extent(&v.fields, 0)));
+        header_len + extent + footer_len + checksum_len
+    }
 
-        header_len + fields_end + footer_len + checksum_len
+    /// 按帧数据中的判别值确定帧长度，未定义消息布局时与最小帧长相同
+    ///
+    /// 判别字段尚未完整到达或无法读取时返回 `None`。
+    pub fn frame_length_for(&self, data: &[u8]) -> Option<usize> {
+        if self.variants.is_empty() && self.default_variant.is_none() {
+            return Some(self.min_frame_length());
+        }
+        let variant = self.select_variant(self.discriminator_value(data)?);
+        Some(self.variant_frame_length(variant))
+    }
+
+    /// 从帧数据中读取判别值，判别字段尚未完整到达或无法读取时返回 `None`
+    pub fn discriminator_value(&self, data: &[u8]) -> Option<u64> {
+        let name = self.discriminator.as_ref()?;
+        let field = self.fields.iter().find(|f| &f.name == name)?;
+        let size = field.field_type.size().filter(|s| (1..=8).contains(s))?;
+
+        let header_len = self.header.as_ref().map(|h| h.len()).unwrap_or(0);
+        let start = header_len + field.offset;
+        let raw = read_uint(data.get(start..start + size)?, &field.byte_order);
+        match field.bits {
+            Some(ref bits) => bits.extract(raw, size as u32 * 8).ok(),
+            None => Some(raw),
+        }
+    }
+
+    /// 线路数据中可查找的帧头，成帧编码与文本协议的帧头不出现在线路数据中
+    pub fn wire_header(&self) -> &[u8] {
+        if let Some(ref decoder) = self.decoder {
+            return decoder.wire_header();
+        }
+        if self.framing.is_some() || self.text.is_some() {
+            return &[];
+        }
+        self.header.as_deref().unwrap_or_default()
+    }
+
+    /// 能否从字节流中分帧
+    pub fn can_frame(&self) -> bool {
+        self.decoder.is_some() || self.text.is_some() || self.min_frame_length() > 0
+    }
+
+    /// 是否按定界字节或行分帧，解析失败时整帧丢弃
+    pub fn is_delimited(&self) -> bool {
+        self.framing.is_some() || self.text.is_some() || self.decoder.as_ref().is_some_and(|d| d.line_ending().is_some())
+    }
+
+    /// 按判别值选择消息布局
+    pub fn select_variant(&self, value: u64) -> Option<&ProtocolVariant> {
+        self.variants.iter()
+            .find(|v| v.values.contains(&value))
+            .or(self.default_variant.as_ref())
+    }
+
+    /// 按名称查找子结构
+    pub fn find_struct(&self, name: &str) -> Option<&ProtocolStruct> {
+        self.structs.iter().find(|s| s.name == name)
+    }
+
+    /// 字段声明时占用的字节数，变长部分按名义长度计
+    pub fn nominal_size(&self, field: &ProtocolField) -> usize {
+        self.nominal_size_at(field, 0)
+    }
+
+    fn nominal_size_at(&self, field: &ProtocolField, depth: usize) -> usize {
+        if field.count_from.is_some() {
+            return 0;
+        }
+        self.element_size(field, depth) * field.count.unwrap_or(1)
+    }
+
+    /// 单个元素声明时占用的字节数
+    fn element_size(&self, field: &ProtocolField, depth: usize) -> usize {
+        if field.field_type == FieldType::Struct {
+            if depth >= MAX_STRUCT_DEPTH {
+                return 0;
+            }
+            return field.struct_name.as_deref()
+                .and_then(|name| self.find_struct(name))
+                .map(|s| self.fields_extent(&s.fields, depth + 1))
+                .unwrap_or(0);
+        }
+        match field.length_from {
+            Some(_) => field.field_type.size().or(field.length).unwrap_or(0),
+            None => field.field_type.size().or(field.length).unwrap_or(1),
+        }
+    }
+
+    /// 字段列表声明时的结束偏移
+    fn fields_extent(&self, fields: &[ProtocolField], depth: usize) -> usize {
+        fields.iter()
+            .map(|f| f.offset + self.nominal_size_at(f, depth))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// 内置解码器
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum BuiltinDecoder {
+    /// Modbus RTU/ASCII，识别功能码 1–6、15、16、23 的请求、响应及异常响应
+    Modbus { mode: ModbusMode },
+    /// NMEA 0183，校验 `*hh` 并解码 GGA、RMC、GSV、GSA、VTG 语句
+    Nmea,
+}
+
+impl BuiltinDecoder {
+    /// 校验类型
+    pub fn checksum_type(&self) -> ChecksumType {
+        match self {
+            BuiltinDecoder::Modbus { mode } => mode.checksum_type(),
+            BuiltinDecoder::Nmea => ChecksumType::Xor8,
+        }
+    }
+
+    /// 线路数据中的帧起始标记
+    pub fn wire_header(&self) -> &'static [u8] {
+        match self {
+            BuiltinDecoder::Modbus { mode: ModbusMode::Rtu } => &[],
+            BuiltinDecoder::Modbus { mode: ModbusMode::Ascii } => b":",
+            BuiltinDecoder::Nmea => b"$",
+        }
+    }
+
+    /// 按行分帧时的行结束符
+    pub fn line_ending(&self) -> Option<&'static [u8]> {
+        match self {
+            BuiltinDecoder::Modbus { mode: ModbusMode::Rtu } => None,
+            BuiltinDecoder::Modbus { mode: ModbusMode::Ascii } => Some(b"\r\n"),
+            // 兼容只以 "\n" 结束的设备，行尾的 "\r" 在解析时去除
+            BuiltinDecoder::Nmea => Some(b"\n"),
+        }
     }
 }
 
@@ -193,6 +671,10 @@ pub enum ChecksumType {
     Crc32,
     /// 异或校验
     Xor8,
+    /// 纵向冗余校验：累加和的补码（Modbus ASCII）
+    Lrc8,
+    /// 自定义参数的 CRC
+    Crc(CrcSpec),
 }
 
 #[allow(dead_code)]
@@ -201,9 +683,10 @@ impl ChecksumType {
     pub fn size(&self) -> usize {
         match self {
             ChecksumType::None => 0,
-            ChecksumType::Sum8 | ChecksumType::Crc8 | ChecksumType::Xor8 => 1,
+            ChecksumType::Sum8 | ChecksumType::Crc8 | ChecksumType::Xor8 | ChecksumType::Lrc8 => 1,
             ChecksumType::Sum16 | ChecksumType::Crc16 => 2,
             ChecksumType::Crc32 => 4,
+            ChecksumType::Crc(spec) => spec.size(),
         }
     }
 }
@@ -214,6 +697,242 @@ impl Default for ChecksumType {
     }
 }
 
+/// 字段长度引用：长度 = 引用字段值 × multiplier + adjust
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LengthRef {
+    /// 被引用的字段名称（须为整数类型字段，且声明在前）
+    pub field: String,
+    /// 长度修正值
+    #[serde(default)]
+    pub adjust: i64,
+    /// 长度倍数，例如寄存器个数换算为字节数时为 2
+    #[serde(default = "default_multiplier")]
+    pub multiplier: i64,
+}
+
+impl LengthRef {
+    /// 由引用字段的值计算长度，结果为负时返回 `None`
+    pub fn resolve(&self, value: u64) -> Option<usize> {
+        let length = (value as i64).checked_mul(self.multiplier)?.checked_add(self.adjust)?;
+        usize::try_from(length).ok()
+    }
+}
+
+fn default_multiplier() -> i64 {
+    1
+}
+
+/// 帧长度字段
+///
+/// 长度值计数的范围从 `start` 开始，其后还有 `trailing` 个不计入的字节（如校验值与帧尾）：
+/// 帧总长 = start + 字段值 × multiplier + adjust + trailing。
+/// 两者均缺省为 0 时，字段值即为帧总长。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LengthField {
+    /// 长度字段名称（须为整数类型字段）
+    pub field: String,
+    /// 长度修正值
+    #[serde(default)]
+    pub adjust: i64,
+    /// 长度倍数
+    #[serde(default = "default_multiplier")]
+    pub multiplier: i64,
+    /// 计数范围起始偏移（相对帧起始，含帧头）
+    #[serde(default)]
+    pub start: usize,
+    /// 计数范围之后的字节数
+    #[serde(default)]
+    pub trailing: usize,
+}
+
+impl LengthField {
+    /// 由长度字段的值计算帧总长，结果为负时返回 `None`
+    pub fn frame_length(&self, value: u64) -> Option<usize> {
+        let covered = (value as i64).checked_mul(self.multiplier)?.checked_add(self.adjust)?;
+        let covered = usize::try_from(covered).ok()?;
+        Some(self.start + covered + self.trailing)
+    }
+}
+
+/// 校验布局
+///
+/// 偏移均相对帧起始（含帧头）。默认从第 0 字节计算到校验值之前，校验值紧邻帧尾之前。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecksumLayout {
+    /// 参与计算的起始偏移
+    #[serde(default)]
+    pub start: usize,
+    /// 参与计算的结束偏移（不含），为空时到校验值之前
+    pub end: Option<usize>,
+    /// 校验值偏移，为空时紧邻帧尾之前
+    pub position: Option<usize>,
+    /// 多字节校验值的字节序
+    #[serde(default)]
+    pub byte_order: ByteOrder,
+}
+
+/// 校验结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecksumResult {
+    /// 校验类型
+    pub checksum_type: ChecksumType,
+    /// 按数据计算出的校验值
+    #[serde(with = "hex_u64")]
+    pub expected: u64,
+    /// 帧中携带的校验值
+    #[serde(with = "hex_u64")]
+    pub actual: u64,
+    /// 是否一致
+    pub valid: bool,
+}
+
+/// 字段解码错误类型
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum FieldErrorKind {
+    /// 数据不足
+    InsufficientData,
+    /// 长度无效
+    InvalidLength,
+    /// 引用的字段不存在或尚未解析
+    UnresolvedReference,
+    /// 位范围超出容器
+    InvalidBitRange,
+    /// 字段类型不支持该用法
+    UnsupportedType,
+    /// 子结构嵌套过深
+    NestingTooDeep,
+    /// 数据不符合字段类型的编码（如 BCD 中出现非十进制数字）
+    InvalidEncoding,
+}
+
+/// 字段解码错误
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldError {
+    /// 错误类型
+    pub kind: FieldErrorKind,
+    /// 错误信息
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(kind: FieldErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+}
+
+/// 字段值
+///
+/// 64 位整数（无符号、有符号、时间戳与枚举值）在 ±(2^53 - 1) 以内时序列化为数字，
+/// 超出时序列化为十进制字符串，避免在前端丢失精度。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "camelCase")]
+pub enum FieldValue {
+    /// 无符号整数
+    Unsigned(#[serde(with = "safe_u64")] u64),
+    /// 有符号整数
+    Signed(#[serde(with = "safe_i64")] i64),
+    /// 浮点数
+    Float(f64),
+    /// 布尔（标志位）
+    Bool(bool),
+    /// 字符串
+    String(String),
+    /// 字节数组
+    Bytes(Vec<u8>),
+    /// Unix 时间戳（毫秒）
+    Timestamp(#[serde(with = "safe_i64")] i64),
+    /// 取值映射命中的枚举值
+    Enum {
+        #[serde(with = "safe_u64")]
+        value: u64,
+        label: String,
+    },
+    /// 解码失败
+    Error(FieldError),
+}
+
+impl FieldValue {
+    /// 转为数值，非数值类型返回 `None`
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            FieldValue::Unsigned(v) => Some(*v as f64),
+            FieldValue::Signed(v) => Some(*v as f64),
+            FieldValue::Float(v) => Some(*v),
+            FieldValue::Bool(v) => Some(if *v { 1.0 } else { 0.0 }),
+            FieldValue::Timestamp(v) => Some(*v as f64),
+            FieldValue::Enum { value, .. } => Some(*value as f64),
+            FieldValue::String(_) | FieldValue::Bytes(_) | FieldValue::Error(_) => None,
+        }
+    }
+}
+
+/// JavaScript 可精确表示的最大整数 2^53 - 1
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// u64 在安全范围内序列化为数字，否则为十进制字符串；反序列化同时接受数字和字符串
+mod safe_u64 {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr {
+        Number(u64),
+        Text(String),
+    }
+
+    pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        if *value <= super::MAX_SAFE_INTEGER {
+            serializer.serialize_u64(*value)
+        } else {
+            serializer.serialize_str(&value.to_string())
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        match Repr::deserialize(deserializer)? {
+            Repr::Number(n) => Ok(n),
+            Repr::Text(s) => super::hex_u64::parse(&s).map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+/// `safe_u64` 的有符号版本，按绝对值判断是否在安全范围内
+mod safe_i64 {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr {
+        Number(i64),
+        Text(String),
+    }
+
+    pub fn serialize<S: Serializer>(value: &i64, serializer: S) -> Result<S::Ok, S::Error> {
+        if value.unsigned_abs() <= super::MAX_SAFE_INTEGER {
+            serializer.serialize_i64(*value)
+        } else {
+            serializer.serialize_str(&value.to_string())
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+        match Repr::deserialize(deserializer)? {
+            Repr::Number(n) => Ok(n),
+            Repr::Text(s) => s.trim().parse::<i64>()
+                .map_err(|e| serde::de::Error::custom(format!("无效的数值 {}: {}", s, e))),
+        }
+    }
+}
+
 /// 解析后的字段值
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -225,9 +944,20 @@ pub struct ParsedField {
     /// 原始字节
     pub raw_bytes: Vec<u8>,
     /// 解析后的值
-    pub value: String,
+    pub value: FieldValue,
+    /// 显示文本，解码失败时为空
+    pub display: Option<String>,
+    /// 原始数值（数值类型字段）
+    pub raw_value: Option<f64>,
+    /// 换算后的工程值
+    pub scaled_value: Option<f64>,
+    /// 单位
+    pub unit: Option<String>,
     /// 描述
     pub description: Option<String>,
+    /// 数组元素或子结构字段
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<ParsedField>,
 }
 
 /// 解析结果
@@ -244,4 +974,62 @@ pub struct ParsedFrame {
     pub valid: bool,
     /// 错误信息
     pub error: Option<String>,
+    /// 校验结果
+    pub checksum: Option<ChecksumResult>,
+    /// 命中的消息布局名称
+    #[serde(default)]
+    pub variant: Option<String>,
+    /// 解除转义后的帧数据（配置了成帧编码时），字段与校验均基于此数据
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unstuffed: Option<Vec<u8>>,
+    /// 自动识别时得分相同或接近的其他协议名称，按得分从高到低
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ambiguous: Vec<String>,
+    /// 内置 Modbus 解码器给出的报文
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modbus: Option<ModbusMessage>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bit_range_rejects_out_of_range_offsets() {
+        let bits = BitRange { offset: u32::MAX, width: 4, order: BitOrder::LsbFirst };
+        assert!(bits.extract(0xFF, 8).is_err());
+        assert!(bits.insert(0, 1, 8).is_err());
+
+        let bits = BitRange { offset: 4, width: 4, order: BitOrder::MsbFirst };
+        assert_eq!(bits.extract(0xA5, 8), Ok(0x5));
+        assert_eq!(bits.insert(0xA0, 0x3, 8), Ok(0xA3));
+    }
+
+    #[test]
+    fn integer_values_keep_full_precision() {
+        let value = serde_json::to_value(FieldValue::Unsigned(u64::MAX)).unwrap();
+        assert_eq!(value, serde_json::json!({ "type": "unsigned", "value": "18446744073709551615" }));
+        assert!(matches!(serde_json::from_value(value).unwrap(), FieldValue::Unsigned(u64::MAX)));
+
+        let value = serde_json::to_value(FieldValue::Unsigned(MAX_SAFE_INTEGER)).unwrap();
+        assert_eq!(value["value"], 9007199254740991u64);
+
+        let value = serde_json::to_value(FieldValue::Signed(i64::MIN)).unwrap();
+        assert_eq!(value["value"], "-9223372036854775808");
+        assert!(matches!(serde_json::from_value(value).unwrap(), FieldValue::Signed(i64::MIN)));
+        let value = serde_json::to_value(FieldValue::Signed(-(MAX_SAFE_INTEGER as i64))).unwrap();
+        assert_eq!(value["value"], -9007199254740991i64);
+
+        let value = serde_json::to_value(FieldValue::Timestamp(1_700_000_000_000)).unwrap();
+        assert_eq!(value["value"], 1_700_000_000_000i64);
+
+        let value = serde_json::to_value(FieldValue::Enum { value: (1 << 53) + 1, label: "A".to_string() }).unwrap();
+        assert_eq!(value["value"]["value"], "9007199254740993");
+
+        // 旧数据中的数值与十六进制字符串仍可读取
+        let value = serde_json::json!({ "type": "unsigned", "value": 7 });
+        assert!(matches!(serde_json::from_value(value).unwrap(), FieldValue::Unsigned(7)));
+        let value = serde_json::json!({ "type": "unsigned", "value": "0xFF" });
+        assert!(matches!(serde_json::from_value(value).unwrap(), FieldValue::Unsigned(0xFF)));
+    }
 }
@@ -0,0 +1,545 @@
+use super::checksum::read_uint;
+use super::modbus::{rtu_checksum_valid, rtu_frame_lengths, ModbusTracker, ModbusTransaction};
+use super::parser::{best_match, max_score, ProtocolParser};
+use super::protocol::*;
+
+/// 自动识别暂缓完整帧时，超出该帧长度后最多再等待的字节数
+const DEFER_BYTE_BUDGET: usize = 32;
+
+/// 帧间隔（毫秒），暂缓的完整帧在此时间内没有新数据到达时输出
+pub const FRAME_GAP_MS: i64 = 20;
+
+/// 流式帧提取器
+///
+/// 每个会话（方向）持有一个实例，按激活协议从接收字节流中切分完整帧：
+/// 先查找帧头，再依次根据长度字段、帧尾或最小帧长确定帧长度，
+/// 校验失败或无法成帧时丢弃一个字节重新同步。
+/// 配置了成帧编码的协议按定界字节分帧，文本协议按行分帧，解析失败时整帧丢弃。
+/// 自动识别模式下在缓冲区起始处尝试全部协议，取得分最高的完整帧；
+/// 仍在等待数据的协议可能得分更高时暂缓，避免帧头较短的协议截走较长帧的开头；
+/// 暂缓以帧间隔和帧后字节数为限，超出后输出已完整匹配的帧。
+/// 内置 Modbus 解码器的报文按出现顺序逐帧参与请求与响应配对。
+pub struct StreamDecoder {
+    /// 待处理的数据
+    buffer: Vec<u8>,
+    /// 当前协议标识（ID 与更新时间），协议变化时清空缓冲区
+    protocol_key: Option<(String, i64)>,
+    /// 最近一次收到数据的时间（毫秒）
+    last_data: i64,
+}
+
+/// 一次推入过程中解析出的帧与 Modbus 配对状态
+struct Pairing<'a> {
+    tracker: &'a mut ModbusTracker,
+    timestamp: i64,
+    frames: Vec<(ParsedFrame, Option<ModbusTransaction>)>,
+}
+
+impl Pairing<'_> {
+    /// 记录一帧，内置 Modbus 解码器的报文随即参与配对，保证同一批数据中的响应能参考之前的请求
+    fn push(&mut self, frame: ParsedFrame) {
+        let transaction = frame.modbus.as_ref()
+            .and_then(|message| self.tracker.observe(message, self.timestamp));
+        self.frames.push((frame, transaction));
+    }
+}
+
+/// 帧长度判断结果
+enum FrameLength {
+    /// 已确定帧长度
+    Known(usize),
+    /// 数据不足，等待更多数据
+    Incomplete,
+    /// 当前位置无法成帧
+    Invalid,
+}
+
+impl StreamDecoder {
+    /// 创建新的提取器
+    pub fn new() -> Self {
+        Self {
+            buffer: Vec::new(),
+            protocol_key: None,
+            last_data: 0,
+        }
+    }
+
+    /// 缓冲区中是否没有待处理的数据
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// 清空缓冲区
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+    }
+
+    /// 推入数据，返回新解析出的帧及其完成的 Modbus 请求与响应配对
+    ///
+    /// `modbus` 记录等待响应的请求，用于区分格式相同的请求与响应；`timestamp` 为数据到达时间。
+    pub fn push(
+        &mut self,
+        data: &[u8],
+        parser: &ProtocolParser,
+        modbus: &mut ModbusTracker,
+        timestamp: i64,
+    ) -> Vec<(ParsedFrame, Option<ModbusTransaction>)> {
+        let mut pairing = Pairing { tracker: modbus, timestamp, frames: Vec::new() };
+        if !data.is_empty() {
+            self.last_data = timestamp;
+        }
+
+        if parser.auto_detect() {
+            // 自动识别模式不绑定单个协议，以空 ID 标识
+            self.bind((String::new(), 0));
+            self.buffer.extend_from_slice(data);
+            self.extract_auto(parser, &mut pairing, false);
+            return pairing.frames;
+        }
+
+        let Some(protocol) = parser.active_protocol() else {
+            self.reset();
+            self.protocol_key = None;
+            return Vec::new();
+        };
+
+        self.bind((protocol.id.clone(), protocol.updated_at));
+        self.buffer.extend_from_slice(data);
+        self.extract(parser, protocol, &mut pairing);
+        pairing.frames
+    }
+
+    /// 没有新数据时调用，距上次收到数据超过帧间隔时输出自动识别暂缓的完整帧
+    pub fn poll(
+        &mut self,
+        parser: &ProtocolParser,
+        modbus: &mut ModbusTracker,
+        timestamp: i64,
+    ) -> Vec<(ParsedFrame, Option<ModbusTransaction>)> {
+        let mut pairing = Pairing { tracker: modbus, timestamp, frames: Vec::new() };
+        let idle = timestamp - self.last_data >= FRAME_GAP_MS;
+        if idle && !self.buffer.is_empty() && parser.auto_detect() && self.protocol_key.is_some() {
+            self.extract_auto(parser, &mut pairing, true);
+        }
+        pairing.frames
+    }
+
+    /// 切换协议标识，变化时清空缓冲区
+    fn bind(&mut self, key: (String, i64)) {
+        if self.protocol_key.as_ref() != Some(&key) {
+            self.reset();
+            self.protocol_key = Some(key);
+        }
+    }
+
+    /// 从缓冲区中提取所有完整帧
+    fn extract(&mut self, parser: &ProtocolParser, protocol: &Protocol, pairing: &mut Pairing) {
+        let header = protocol.wire_header().to_vec();
+
+        // 既无帧头帧尾又无字段时无法分帧
+        if !protocol.can_frame() {
+            self.reset();
+            return;
+        }
+
+        loop {
+            // 对齐到帧头
+            if !header.is_empty() {
+                match find(&self.buffer, &header) {
+                    Some(pos) => {
+                        self.buffer.drain(..pos);
+                    }
+                    None => {
+                        // 保留可能是帧头前缀的尾部字节
+                        let keep = (header.len() - 1).min(self.buffer.len());
+                        self.buffer.drain(..self.buffer.len() - keep);
+                        break;
+                    }
+                }
+            }
+
+            let length = match frame_length(&self.buffer, protocol) {
+                FrameLength::Known(length) => length,
+                FrameLength::Incomplete => break,
+                FrameLength::Invalid => {
+                    self.buffer.drain(..1);
+                    continue;
+                }
+            };
+
+            if self.buffer.len() < length {
+                break;
+            }
+
+            let frame = parser.parse_paired(&self.buffer[..length], protocol, Some(&*pairing.tracker));
+            if frame.valid {
+                self.buffer.drain(..length);
+                pairing.push(frame);
+            } else {
+                // 校验失败的帧仍然上报，便于排查
+                if frame.checksum.as_ref().is_some_and(|c| !c.valid) {
+                    pairing.push(frame);
+                }
+                let skip = if protocol.is_delimited() { length } else { 1 };
+                self.buffer.drain(..skip);
+            }
+        }
+    }
+
+    /// 自动识别模式下从缓冲区中提取所有完整帧
+    ///
+    /// 帧头与缓冲区起始处一致的协议各自确定帧长度并解析评分；仍在等待数据的协议可能
+    /// 取得的最高分超过已完整匹配的最高分时暂停，没有完整匹配时丢弃一个字节重新同步。
+    /// 已有完整匹配时，帧后数据超过字节预算或 `idle`（帧间隔内无新数据）时不再暂停。
+    fn extract_auto(&mut self, parser: &ProtocolParser, pairing: &mut Pairing, idle: bool) {
+        let protocols: Vec<&Protocol> = parser.protocols_by_name()
+            .into_iter()
+            .filter(|p| p.can_frame())
+            .collect();
+
+        if protocols.is_empty() {
+            self.reset();
+            return;
+        }
+
+        while !self.buffer.is_empty() {
+            let mut candidates = Vec::new();
+            // 仍在等待数据的协议可能取得的最高分
+            let mut waiting: Option<u32> = None;
+            let mut wait = |protocol: &Protocol| {
+                waiting = waiting.max(Some(max_score(protocol)));
+            };
+
+            for protocol in &protocols {
+                let header = protocol.wire_header();
+                let prefix = header.len().min(self.buffer.len());
+                if self.buffer[..prefix] != header[..prefix] {
+                    continue;
+                }
+                if self.buffer.len() < header.len() {
+                    wait(protocol);
+                    continue;
+                }
+
+                match frame_length(&self.buffer, protocol) {
+                    FrameLength::Known(length) if self.buffer.len() < length => wait(protocol),
+                    FrameLength::Known(length) => {
+                        candidates.extend(parser.match_protocol(&self.buffer[..length], protocol, Some(&*pairing.tracker)));
+                    }
+                    FrameLength::Incomplete => wait(protocol),
+                    FrameLength::Invalid => {}
+                }
+            }
+
+            let best_score = candidates.iter().map(|(score, _)| *score).max();
+            let best_length = candidates.iter()
+                .find(|(score, _)| Some(*score) == best_score)
+                .map(|(_, frame)| frame.raw_data.len());
+            if waiting > best_score {
+                let defer = match best_length {
+                    Some(length) => !idle && self.buffer.len() < length + DEFER_BYTE_BUDGET,
+                    None => self.buffer.len() <= MAX_FRAME_LENGTH,
+                };
+                if defer {
+                    break;
+                }
+            }
+
+            match best_match(candidates) {
+                Some(frame) => {
+                    self.buffer.drain(..frame.raw_data.len());
+                    pairing.push(frame);
+                }
+                None => {
+                    self.buffer.drain(..1);
+                }
+            }
+        }
+    }
+}
+
+impl Default for StreamDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 判断缓冲区起始处的帧长度
+fn frame_length(buffer: &[u8], protocol: &Protocol) -> FrameLength {
+    let min_length = protocol.min_frame_length();
+
+    if let Some(ref decoder) = protocol.decoder {
+        return match decoder.line_ending() {
+            Some(line_ending) => line_length(buffer, line_ending),
+            None => rtu_length(buffer),
+        };
+    }
+
+    if let Some(ref text) = protocol.text {
+        return line_length(buffer, text.line_ending.as_bytes());
+    }
+
+    if let Some(ref framing) = protocol.framing {
+        return delimited_length(buffer, framing.delimiter());
+    }
+
+    if let Some(ref length_field) = protocol.length_field {
+        return length_from_field(buffer, protocol, length_field, min_length);
+    }
+
+    if let Some(ref footer) = protocol.footer {
+        if footer.is_empty() {
+            return FrameLength::Known(min_length);
+        }
+        // 帧尾不会出现在最小帧长之前
+        let search_from = min_length.saturating_sub(footer.len());
+        if buffer.len() <= search_from {
+            return FrameLength::Incomplete;
+        }
+        return match find(&buffer[search_from..], footer) {
+            Some(pos) => FrameLength::Known(search_from + pos + footer.len()),
+            None if buffer.len() > MAX_FRAME_LENGTH => FrameLength::Invalid,
+            None => FrameLength::Incomplete,
+        };
+    }
+
+    // 帧长取决于判别值选中的消息布局
+    match protocol.frame_length_for(buffer) {
+        Some(length) => FrameLength::Known(length),
+        None if buffer.len() < min_length => FrameLength::Incomplete,
+        None => FrameLength::Known(min_length),
+    }
+}
+
+/// 按定界字节确定帧长度，跳过前导定界字节（帧开始标志或空帧）
+fn delimited_length(buffer: &[u8], delimiter: u8) -> FrameLength {
+    let start = buffer.iter().take_while(|b| **b == delimiter).count();
+    match buffer[start..].iter().position(|b| *b == delimiter) {
+        Some(pos) => FrameLength::Known(start + pos + 1),
+        None if buffer.len() > MAX_FRAME_LENGTH => FrameLength::Invalid,
+        None => FrameLength::Incomplete,
+    }
+}
+
+/// 按行结束符确定帧长度（含结束符）
+fn line_length(buffer: &[u8], line_ending: &[u8]) -> FrameLength {
+    match find(buffer, line_ending) {
+        Some(pos) => FrameLength::Known(pos + line_ending.len()),
+        None if buffer.len() > MAX_FRAME_LENGTH => FrameLength::Invalid,
+        None => FrameLength::Incomplete,
+    }
+}
+
+/// 按 Modbus RTU 功能码确定帧长度，候选长度中 CRC 正确者即为帧长
+fn rtu_length(buffer: &[u8]) -> FrameLength {
+    let lengths = rtu_frame_lengths(buffer);
+    let complete = lengths.iter()
+        .copied()
+        .filter(|len| *len <= buffer.len())
+        .find(|len| rtu_checksum_valid(&buffer[..*len]));
+
+    match complete {
+        Some(length) => FrameLength::Known(length),
+        None if lengths.iter().any(|len| *len > buffer.len()) => FrameLength::Incomplete,
+        None => FrameLength::Invalid,
+    }
+}
+
+/// 根据长度字段计算帧长度
+fn length_from_field(buffer: &[u8], protocol: &Protocol, length_field: &LengthField, min_length: usize) -> FrameLength {
+    let Some(field) = protocol.fields.iter().find(|f| f.name == length_field.field) else {
+        return FrameLength::Known(min_length);
+    };
+    let Some(size) = field.field_type.size().filter(|s| *s <= 8) else {
+        return FrameLength::Known(min_length);
+    };
+
+    let header_len = protocol.header.as_ref().map(|h| h.len()).unwrap_or(0);
+    let start = header_len + field.offset;
+    if buffer.len() < start + size {
+        return FrameLength::Incomplete;
+    }
+
+    // 位字段长度与解析时一致，从整数容器中取出
+    let container = read_uint(&buffer[start..start + size], &field.byte_order);
+    let value = match field.bits {
+        Some(ref bits) => match bits.extract(container, size as u32 * 8) {
+            Ok(value) => value,
+            Err(_) => return FrameLength::Invalid,
+        },
+        None => container,
+    };
+    match length_field.frame_length(value) {
+        Some(length) if length >= start + size && length <= MAX_FRAME_LENGTH => FrameLength::Known(length),
+        _ => FrameLength::Invalid,
+    }
+}
+
+/// 查找子序列位置
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() || haystack.len() < needle.len() {
+        return None;
+    }
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::modbus::ModbusMode;
+
+    fn decoder_parser(protocol: Protocol) -> ProtocolParser {
+        let mut parser = ProtocolParser::new();
+        parser.set_active_protocol(Some(protocol.id.clone()));
+        parser.register_protocol(protocol);
+        parser
+    }
+
+    fn push(decoder: &mut StreamDecoder, data: &[u8], parser: &ProtocolParser) -> Vec<ParsedFrame> {
+        decoder.push(data, parser, &mut ModbusTracker::default(), 0)
+            .into_iter()
+            .map(|(frame, _)| frame)
+            .collect()
+    }
+
+    #[test]
+    fn variant_layout_determines_frame_length() {
+        let mut protocol = Protocol::new("命令").with_header(vec![0xAA]);
+        protocol.fields = vec![ProtocolField::new("cmd", FieldType::Uint8, 0)];
+        protocol.discriminator = Some("cmd".to_string());
+        protocol.variants = vec![
+            ProtocolVariant {
+                name: "short".to_string(),
+                values: vec![1],
+                fields: vec![ProtocolField::new("a", FieldType::Uint8, 1)],
+            },
+            ProtocolVariant {
+                name: "long".to_string(),
+                values: vec![2],
+                fields: vec![ProtocolField::new("b", FieldType::Uint32, 1)],
+            },
+        ];
+        let parser = decoder_parser(protocol);
+        let mut decoder = StreamDecoder::new();
+
+        let frames = push(&mut decoder, &[0xAA, 0x02, 0x01, 0x02], &parser);
+        assert!(frames.is_empty());
+        let frames = push(&mut decoder, &[0x03, 0x04, 0xAA, 0x01, 0x05], &parser);
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].raw_data, [0xAA, 0x02, 0x01, 0x02, 0x03, 0x04]);
+        assert_eq!(frames[0].variant.as_deref(), Some("long"));
+        assert_eq!(frames[1].raw_data, [0xAA, 0x01, 0x05]);
+        assert_eq!(frames[1].variant.as_deref(), Some("short"));
+    }
+
+    #[test]
+    fn bit_field_length_determines_frame_length() {
+        // 控制字节高 4 位为标志，低 4 位为负载长度
+        let mut protocol = Protocol::new("位长度").with_header(vec![0xAA]);
+        let mut flags = ProtocolField::new("flags", FieldType::Uint8, 0);
+        flags.bits = Some(BitRange { offset: 4, width: 4, order: BitOrder::LsbFirst });
+        let mut length = ProtocolField::new("length", FieldType::Uint8, 0);
+        length.bits = Some(BitRange { offset: 0, width: 4, order: BitOrder::LsbFirst });
+        protocol.fields = vec![flags, length];
+        protocol.length_field = Some(LengthField {
+            field: "length".to_string(),
+            adjust: 0,
+            multiplier: 1,
+            start: 2,
+            trailing: 0,
+        });
+        let parser = decoder_parser(protocol);
+        let mut decoder = StreamDecoder::new();
+
+        // 整字节值 0xF2 会被误当作 242 字节长度
+        let frames = push(&mut decoder, &[0xAA, 0xF2, 0x01], &parser);
+        assert!(frames.is_empty());
+        let frames = push(&mut decoder, &[0x02, 0xAA, 0x31, 0x07], &parser);
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].raw_data, [0xAA, 0xF2, 0x01, 0x02]);
+        assert!(frames[0].valid, "{:?}", frames[0].error);
+        assert_eq!(frames[1].raw_data, [0xAA, 0x31, 0x07]);
+        assert!(frames[1].valid, "{:?}", frames[1].error);
+    }
+
+    #[test]
+    fn auto_detect_waits_for_stronger_protocol() {
+        let mut short = Protocol::new("短帧").with_header(vec![0xAA]);
+        short.fields = vec![ProtocolField::new("value", FieldType::Uint8, 0)];
+
+        let mut long = Protocol::new("长帧").with_header(vec![0xAA, 0x55]);
+        long.fields = vec![ProtocolField::new("value", FieldType::Uint8, 0)];
+        long.checksum = Some(ChecksumType::Sum8);
+        let mut values = serde_json::Map::new();
+        values.insert("value".to_string(), serde_json::json!(1));
+        let frame = super::super::encode_frame(&long, &values).unwrap();
+        assert_eq!(frame.len(), 4);
+
+        let mut parser = ProtocolParser::new();
+        parser.register_protocol(short);
+        parser.register_protocol(long);
+        parser.set_auto_detect(true);
+        let mut decoder = StreamDecoder::new();
+
+        // 短帧协议此时已能成帧，但长帧协议可能得分更高
+        assert!(push(&mut decoder, &frame[..2], &parser).is_empty());
+        let frames = push(&mut decoder, &frame[2..], &parser);
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].protocol_name, "长帧");
+        assert_eq!(frames[0].raw_data, frame);
+
+        // 长帧协议校验失败时仍按短帧协议成帧
+        let frames = push(&mut decoder, &[0xAA, 0x55, 0x01, 0x7F], &parser);
+        assert_eq!(frames[0].protocol_name, "短帧");
+        assert_eq!(frames[0].raw_data, [0xAA, 0x55]);
+    }
+
+    #[test]
+    fn deferred_frame_is_emitted_after_frame_gap() {
+        let mut binary = Protocol::new("二进制").with_header(vec![0x01]);
+        binary.fields = vec![ProtocolField::new("value", FieldType::Uint8, 0)];
+        let mut modbus = Protocol::new("Modbus");
+        modbus.decoder = Some(BuiltinDecoder::Modbus { mode: ModbusMode::Rtu });
+
+        let mut parser = ProtocolParser::new();
+        parser.register_protocol(binary);
+        parser.register_protocol(modbus);
+        parser.set_auto_detect(true);
+        let mut decoder = StreamDecoder::new();
+        let mut tracker = ModbusTracker::default();
+
+        // Modbus RTU 没有帧头，仍在等待时暂缓已完整的二进制帧
+        assert!(decoder.push(&[0x01, 0x03], &parser, &mut tracker, 100).is_empty());
+        assert!(decoder.poll(&parser, &mut tracker, 100 + FRAME_GAP_MS - 1).is_empty());
+
+        // 帧间隔内没有新数据，不再等待
+        let frames = decoder.poll(&parser, &mut tracker, 100 + FRAME_GAP_MS);
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].0.protocol_name, "二进制");
+        assert_eq!(frames[0].0.raw_data, [0x01, 0x03]);
+        assert!(decoder.poll(&parser, &mut tracker, 1000).is_empty());
+    }
+
+    #[test]
+    fn deferral_is_bounded_by_byte_budget() {
+        let mut binary = Protocol::new("二进制").with_header(vec![0x01]);
+        binary.fields = vec![ProtocolField::new("value", FieldType::Uint8, 0)];
+        let mut text = Protocol::new("文本");
+        text.text = Some(serde_json::from_value(serde_json::json!({ "grammar": { "regex": { "pattern": "x+" } } })).unwrap());
+        text.fields = (0..8).map(|i| ProtocolField::new(&format!("f{}", i), FieldType::String, 0)).collect();
+
+        let mut parser = ProtocolParser::new();
+        parser.register_protocol(binary);
+        parser.register_protocol(text);
+        parser.set_auto_detect(true);
+        let mut decoder = StreamDecoder::new();
+
+        // 文本协议一直等不到换行，帧后数据达到预算时输出二进制帧
+        let mut data = vec![0x01, 0x03];
+        data.extend(std::iter::repeat_n(b'x', DEFER_BYTE_BUDGET - 1));
+        assert!(push(&mut decoder, &data, &parser).is_empty());
+        let frames = push(&mut decoder, b"x", &parser);
+        assert_eq!(frames[0].protocol_name, "二进制");
+        assert_eq!(frames[0].raw_data, [0x01, 0x03]);
+    }
+}
@@ -0,0 +1,186 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use super::encoding::{parse_hex_string, parse_integer, parse_timestamp};
+use super::parser::{describe_value, decoded_field, error_frame};
+use super::protocol::*;
+
+/// 文本协议格式
+///
+/// 按行匹配，字段名对应正则的命名捕获组或键值对的键；字段的偏移、字节序等二进制布局设置不起作用。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextFormat {
+    /// 匹配方式
+    pub grammar: TextGrammar,
+    /// 行结束符，用于流式分行；行尾多余的 "\r" 与 "\n" 自动去除
+    #[serde(default = "default_line_ending")]
+    pub line_ending: String,
+}
+
+fn default_line_ending() -> String {
+    "\n".to_string()
+}
+
+/// 文本匹配方式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TextGrammar {
+    /// 正则表达式，命名捕获组对应同名字段
+    #[serde(rename_all = "camelCase")]
+    Regex { pattern: String },
+    /// 键值对，如 "TEMP=23.4;HUM=55"；未定义的键作为字符串字段给出
+    #[serde(rename_all = "camelCase")]
+    KeyValue {
+        #[serde(default = "default_pair_separator")]
+        pair_separator: String,
+        #[serde(default = "default_key_separator")]
+        key_separator: String,
+    },
+}
+
+fn default_pair_separator() -> String {
+    ";".to_string()
+}
+
+fn default_key_separator() -> String {
+    "=".to_string()
+}
+
+impl TextFormat {
+    /// 编译正则表达式，键值对方式返回 `None`
+    pub fn compile(&self) -> Result<Option<Regex>, String> {
+        match self.grammar {
+            TextGrammar::Regex { ref pattern } => Regex::new(pattern)
+                .map(Some)
+                .map_err(|e| format!("正则表达式无效: {}", e)),
+            TextGrammar::KeyValue { .. } => Ok(None),
+        }
+    }
+}
+
+/// 按文本协议解析一行，`pattern` 为已编译的正则（为空时现场编译）
+pub(super) fn parse_text(data: &[u8], protocol: &Protocol, format: &TextFormat, pattern: Option<&Regex>) -> ParsedFrame {
+    let text = String::from_utf8_lossy(data);
+    let line = text.strip_suffix(format.line_ending.as_str()).unwrap_or(&text);
+    let line = line.trim_end_matches(['\r', '\n']);
+
+    let fields = match format.grammar {
+        TextGrammar::Regex { .. } => {
+            let compiled;
+            let regex = match pattern {
+                Some(regex) => regex,
+                None => match format.compile() {
+                    Ok(Some(regex)) => {
+                        compiled = regex;
+                        &compiled
+                    }
+                    Ok(None) => return error_frame(protocol, data, "正则表达式无效"),
+                    Err(e) => return error_frame(protocol, data, &e),
+                },
+            };
+            match_regex(line, protocol, regex)
+        }
+        TextGrammar::KeyValue { ref pair_separator, ref key_separator } => {
+            match_key_value(line, protocol, pair_separator, key_separator)
+        }
+    };
+
+    let Some(fields) = fields else {
+        return error_frame(protocol, data, "文本不匹配");
+    };
+
+    ParsedFrame {
+        protocol_name: protocol.name.clone(),
+        raw_data: data.to_vec(),
+        fields,
+        valid: true,
+        error: None,
+        checksum: None,
+        variant: None,
+        unstuffed: None,
+        ambiguous: Vec::new(),
+        modbus: None,
+    }
+}
+
+/// 按正则匹配，未参与匹配的捕获组对应的字段不输出
+fn match_regex(line: &str, protocol: &Protocol, regex: &Regex) -> Option<Vec<ParsedField>> {
+    let captures = regex.captures(line)?;
+    let fields = protocol.fields.iter()
+        .filter_map(|field_def| {
+            let capture = captures.name(&field_def.name)?;
+            Some(text_field(field_def, field_def.name.clone(), capture.as_str()))
+        })
+        .collect();
+    Some(fields)
+}
+
+/// 按键值对匹配，没有任何键值对时视为不匹配
+fn match_key_value(line: &str, protocol: &Protocol, pair_separator: &str, key_separator: &str) -> Option<Vec<ParsedField>> {
+    let fields: Vec<ParsedField> = line.split(pair_separator)
+        .filter_map(|pair| pair.split_once(key_separator))
+        .map(|(key, value)| {
+            let (key, value) = (key.trim(), value.trim());
+            match protocol.fields.iter().find(|f| f.name == key) {
+                Some(field_def) => text_field(field_def, key.to_string(), value),
+                None => text_field(&ProtocolField::new(key, FieldType::String, 0), key.to_string(), value),
+            }
+        })
+        .collect();
+    (!fields.is_empty()).then_some(fields)
+}
+
+/// 由捕获的文本构造字段
+fn text_field(field_def: &ProtocolField, name: String, text: &str) -> ParsedField {
+    let decoded = describe_value(field_def, decode_text_value(text, field_def), 2);
+    decoded_field(field_def, name, text.as_bytes().to_vec(), decoded)
+}
+
+/// 按字段类型解析文本
+fn decode_text_value(text: &str, field_def: &ProtocolField) -> Result<(FieldValue, Option<u64>), FieldError> {
+    let invalid = || FieldError::new(FieldErrorKind::InvalidEncoding, format!("无法按 {:?} 解析: {}", field_def.field_type, text));
+    let field_type = &field_def.field_type;
+
+    let decoded = match field_type {
+        FieldType::Uint8 | FieldType::Uint16 | FieldType::Uint32 | FieldType::Uint64 | FieldType::Bcd => {
+            let value = parse_integer(text).and_then(|v| u64::try_from(v).ok()).ok_or_else(invalid)?;
+            (FieldValue::Unsigned(value), Some(value))
+        }
+        FieldType::Int8 | FieldType::Int16 | FieldType::Int32 | FieldType::Int64 => {
+            let value = parse_integer(text).and_then(|v| i64::try_from(v).ok()).ok_or_else(invalid)?;
+            (FieldValue::Signed(value), Some(value as u64))
+        }
+        FieldType::AsciiHex => {
+            let digits = text.trim();
+            let digits = digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")).unwrap_or(digits);
+            let value = u64::from_str_radix(digits, 16).map_err(|_| invalid())?;
+            (FieldValue::Unsigned(value), Some(value))
+        }
+        FieldType::Float16 | FieldType::Float32 | FieldType::Float64 => {
+            let value = text.trim().parse::<f64>().map_err(|_| invalid())?;
+            (FieldValue::Float(value), None)
+        }
+        FieldType::Bool => {
+            let value = match text.trim().to_ascii_lowercase().as_str() {
+                "1" | "true" | "on" | "yes" => true,
+                "0" | "false" | "off" | "no" => false,
+                _ => return Err(invalid()),
+            };
+            (FieldValue::Bool(value), None)
+        }
+        FieldType::Timestamp | FieldType::TimestampMs => {
+            let unit = if *field_type == FieldType::Timestamp { 1000 } else { 1 };
+            let millis = match parse_integer(text).and_then(|v| i64::try_from(v).ok()) {
+                Some(value) => value.checked_mul(unit).ok_or_else(invalid)?,
+                None => parse_timestamp(text).ok_or_else(invalid)?,
+            };
+            (FieldValue::Timestamp(millis), None)
+        }
+        FieldType::String | FieldType::CString => (FieldValue::String(text.to_string()), None),
+        FieldType::Bytes | FieldType::Hex => (FieldValue::Bytes(parse_hex_string(text).map_err(|_| invalid())?), None),
+        FieldType::Struct => {
+            return Err(FieldError::new(FieldErrorKind::UnsupportedType, "文本协议不支持子结构"));
+        }
+    };
+    Ok(decoded)
+}
@@ -0,0 +1,534 @@
+use serde::{Deserialize, Serialize};
+use super::framing::Framing;
+use super::protocol::*;
+use super::text::{TextFormat, TextGrammar};
+
+/// 诊断级别
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// 错误，协议无法保存
+    Error,
+    /// 警告，仅作提示
+    Warning,
+}
+
+/// 协议诊断信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    /// 级别
+    pub severity: Severity,
+    /// 相关字段，子结构与消息布局中的字段以 "结构名.字段名" 表示
+    pub field: Option<String>,
+    /// 说明
+    pub message: String,
+}
+
+/// 诊断收集器
+#[derive(Default)]
+struct Diagnostics {
+    items: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    fn error(&mut self, field: Option<String>, message: String) {
+        self.items.push(Diagnostic { severity: Severity::Error, field, message });
+    }
+
+    fn warning(&mut self, field: Option<String>, message: String) {
+        self.items.push(Diagnostic { severity: Severity::Warning, field, message });
+    }
+}
+
+/// 校验协议定义，返回全部诊断信息
+pub fn validate_protocol(protocol: &Protocol) -> Vec<Diagnostic> {
+    let mut diagnostics = Diagnostics::default();
+
+    if protocol.name.trim().is_empty() {
+        diagnostics.error(None, "协议名称不能为空".to_string());
+    }
+
+    if protocol.decoder.is_some() {
+        validate_builtin(protocol, &mut diagnostics);
+        return diagnostics.items;
+    }
+
+    if let Some(ref text) = protocol.text {
+        validate_text(protocol, text, &mut diagnostics);
+        return diagnostics.items;
+    }
+
+    if let Some(ChecksumType::Crc(spec)) = &protocol.checksum {
+        if let Err(e) = spec.validate() {
+            diagnostics.error(None, format!("校验类型: {}", e));
+        }
+    }
+
+    if let Some(Framing::Hdlc(params)) = &protocol.framing {
+        if let Err(e) = params.validate() {
+            diagnostics.error(None, format!("成帧编码: {}", e));
+        }
+    }
+
+    let header_len = protocol.header.as_ref().map(|h| h.len()).unwrap_or(0);
+    validate_fields(protocol, &protocol.fields, &[], None, header_len, &mut diagnostics);
+
+    for structure in &protocol.structs {
+        validate_fields(protocol, &structure.fields, &[], Some(&structure.name), 0, &mut diagnostics);
+        let used = all_fields(protocol)
+            .any(|f| f.struct_name.as_deref() == Some(structure.name.as_str()));
+        if !used {
+            diagnostics.warning(None, format!("子结构 {} 未被任何字段使用", structure.name));
+        }
+    }
+
+    validate_variants(protocol, header_len, &mut diagnostics);
+    validate_reachability(protocol, &mut diagnostics);
+    validate_frame_length(protocol, header_len, &mut diagnostics);
+    validate_length_field(protocol, &mut diagnostics);
+    validate_checksum_layout(protocol, header_len, &mut diagnostics);
+
+    diagnostics.items
+}
+
+/// 校验协议，存在错误时返回首个错误
+pub fn check_protocol(protocol: &Protocol) -> Result<(), String> {
+    match validate_protocol(protocol).into_iter().find(|d| d.severity == Severity::Error) {
+        Some(Diagnostic { field: Some(field), message, .. }) => Err(format!("字段 {}: {}", field, message)),
+        Some(Diagnostic { field: None, message, .. }) => Err(message),
+        None => Ok(()),
+    }
+}
+
+/// 协议中的全部字段定义
+fn all_fields(protocol: &Protocol) -> impl Iterator<Item = &ProtocolField> {
+    protocol.fields.iter()
+        .chain(protocol.structs.iter().flat_map(|s| &s.fields))
+        .chain(protocol.variants.iter().chain(protocol.default_variant.as_ref()).flat_map(|v| &v.fields))
+}
+
+/// 校验使用内置解码器的协议，字段定义与帧格式设置均不起作用
+fn validate_builtin(protocol: &Protocol, diagnostics: &mut Diagnostics) {
+    if protocol.text.is_some() {
+        diagnostics.error(None, "内置解码器与文本协议格式不能同时设置".to_string());
+    }
+
+    let has_layout = !protocol.fields.is_empty() || !protocol.variants.is_empty()
+        || protocol.header.as_ref().is_some_and(|h| !h.is_empty())
+        || protocol.footer.as_ref().is_some_and(|f| !f.is_empty())
+        || protocol.checksum.as_ref().is_some_and(|c| *c != ChecksumType::None)
+        || protocol.length_field.is_some() || protocol.framing.is_some();
+    if has_layout {
+        diagnostics.warning(None, "使用内置解码器时字段定义与帧格式设置不起作用".to_string());
+    }
+}
+
+/// 校验文本协议，二进制布局相关的设置不起作用，仅给出警告
+fn validate_text(protocol: &Protocol, text: &TextFormat, diagnostics: &mut Diagnostics) {
+    if text.line_ending.is_empty() {
+        diagnostics.error(None, "行结束符不能为空".to_string());
+    }
+
+    for (index, field) in protocol.fields.iter().enumerate() {
+        if field.name.trim().is_empty() {
+            diagnostics.error(Some(format!("#{}", index + 1)), "名称不能为空".to_string());
+        } else if protocol.fields[..index].iter().any(|f| f.name == field.name) {
+            diagnostics.error(Some(field.name.clone()), "名称重复".to_string());
+        }
+        if field.field_type == FieldType::Struct || field.is_array() {
+            diagnostics.error(Some(field.name.clone()), "文本协议不支持子结构与数组".to_string());
+        }
+        if field.bits.is_some() || field.length_from.is_some() {
+            diagnostics.warning(Some(field.name.clone()), "文本协议中位域与长度引用不起作用".to_string());
+        }
+    }
+
+    match text.grammar {
+        TextGrammar::Regex { .. } => match text.compile() {
+            Ok(Some(regex)) => {
+                let groups: Vec<&str> = regex.capture_names().flatten().collect();
+                for field in &protocol.fields {
+                    if !groups.contains(&field.name.as_str()) {
+                        diagnostics.warning(Some(field.name.clone()), "正则表达式中没有同名的命名捕获组".to_string());
+                    }
+                }
+            }
+            Ok(None) => {}
+            Err(e) => diagnostics.error(None, e),
+        },
+        TextGrammar::KeyValue { ref pair_separator, ref key_separator } => {
+            if pair_separator.is_empty() || key_separator.is_empty() {
+                diagnostics.error(None, "键值对分隔符不能为空".to_string());
+            } else if pair_separator == key_separator {
+                diagnostics.error(None, "键值对分隔符与键值分隔符不能相同".to_string());
+            }
+        }
+    }
+
+    if protocol.checksum.is_some() || protocol.length_field.is_some() || protocol.framing.is_some() || !protocol.variants.is_empty() {
+        diagnostics.warning(None, "文本协议中校验、长度字段、成帧编码与消息布局设置不起作用".to_string());
+    }
+}
+
+/// 校验字段列表
+///
+/// `outer` 为可被引用的外层字段，`scope` 为所属子结构或消息布局名称，
+/// `base` 为字段偏移起点相对帧起始的距离。
+fn validate_fields(
+    protocol: &Protocol,
+    fields: &[ProtocolField],
+    outer: &[ProtocolField],
+    scope: Option<&str>,
+    base: usize,
+    diagnostics: &mut Diagnostics,
+) {
+    let label = |field: &ProtocolField, index: usize| {
+        let name = if field.name.trim().is_empty() {
+            format!("#{}", index + 1)
+        } else {
+            field.name.clone()
+        };
+        match scope {
+            Some(scope) => format!("{}.{}", scope, name),
+            None => name,
+        }
+    };
+
+    for (index, field) in fields.iter().enumerate() {
+        let name = Some(label(field, index));
+
+        if field.name.trim().is_empty() {
+            diagnostics.error(name.clone(), "名称不能为空".to_string());
+        } else if fields[..index].iter().any(|f| f.name == field.name) {
+            diagnostics.error(name.clone(), "名称重复".to_string());
+        }
+
+        // 长度与个数只能引用前面已解析的整数字段
+        let earlier: Vec<&ProtocolField> = outer.iter().chain(&fields[..index]).collect();
+        for reference in [&field.length_from, &field.count_from].into_iter().flatten() {
+            match earlier.iter().find(|f| f.name == reference.field) {
+                Some(target) if target.field_type.is_integral() => {}
+                Some(_) => diagnostics.error(name.clone(), format!("引用的字段 {} 不是整数类型", reference.field)),
+                None => diagnostics.error(name.clone(), format!("引用的字段 {} 不存在或声明在后", reference.field)),
+            }
+        }
+
+        let requires_length = field.field_type.requires_length();
+        if requires_length && field.length.is_none() && field.length_from.is_none() {
+            diagnostics.error(name.clone(), "未设置长度".to_string());
+        }
+        if !requires_length && field.field_type != FieldType::CString && field.length_from.is_some() {
+            diagnostics.warning(name.clone(), "定长类型的长度引用不起作用".to_string());
+        }
+        // BCD 与 ASCII 十六进制须能容纳于 64 位整数
+        let max_length = match field.field_type {
+            FieldType::Bcd => Some(9),
+            FieldType::AsciiHex => Some(16),
+            _ => None,
+        };
+        if let (Some(max_length), Some(length)) = (max_length, field.length) {
+            if length > max_length {
+                diagnostics.error(name.clone(), format!("长度 {} 超出上限 {}", length, max_length));
+            }
+        }
+
+        if field.field_type == FieldType::Struct {
+            let struct_name = field.struct_name.as_deref().unwrap_or_default();
+            if protocol.find_struct(struct_name).is_none() {
+                diagnostics.error(name.clone(), format!("子结构不存在: {}", struct_name));
+            }
+        }
+
+        if let Some(ref bits) = field.bits {
+            if !field.field_type.is_integer() {
+                diagnostics.error(name.clone(), "位字段须使用整数类型".to_string());
+            } else if let Some(size) = field.field_type.size() {
+                if let Err(e) = bits.extract(0, size as u32 * 8) {
+                    diagnostics.error(name.clone(), e);
+                }
+            }
+        }
+
+        if field.value_map.is_some() && !field.field_type.is_integral() {
+            diagnostics.warning(name.clone(), "取值映射仅对整数及位字段生效".to_string());
+        }
+
+        let end = base + field.offset + protocol.nominal_size(field);
+        if end > MAX_FRAME_LENGTH {
+            diagnostics.error(name.clone(), format!("偏移超出范围: 结束于第 {} 字节, 上限 {}", end, MAX_FRAME_LENGTH));
+        }
+    }
+
+    // 消息布局与公共字段共用偏移，一并检查重叠与空隙
+    let entries: Vec<LayoutEntry> = outer.iter()
+        .map(|f| LayoutEntry { name: f.name.clone(), field: f, own: false })
+        .chain(fields.iter().enumerate().map(|(index, f)| LayoutEntry { name: label(f, index), field: f, own: true }))
+        .collect();
+    validate_layout(protocol, &entries, diagnostics);
+}
+
+/// 布局检查条目
+struct LayoutEntry<'a> {
+    /// 诊断中使用的字段名称
+    name: String,
+    field: &'a ProtocolField,
+    /// 是否属于当前检查的字段列表，外层字段之间的问题不重复报告
+    own: bool,
+}
+
+/// 检查字段之间的重叠与空隙
+fn validate_layout(protocol: &Protocol, entries: &[LayoutEntry], diagnostics: &mut Diagnostics) {
+    let mut ranges: Vec<(usize, usize, usize)> = entries.iter()
+        .enumerate()
+        .map(|(index, e)| (e.field.offset, e.field.offset + protocol.nominal_size(e.field), index))
+        .collect();
+    ranges.sort();
+
+    let mut covered = 0;
+    // 已检查字段中结束最晚的非位字段
+    let mut last: Option<(usize, usize)> = None;
+    for &(start, end, index) in &ranges {
+        let entry = &entries[index];
+        if start > covered && entry.own {
+            diagnostics.warning(Some(entry.name.clone()), format!("字节 {}..{} 未被任何字段覆盖", covered, start));
+        }
+        covered = covered.max(end);
+
+        // 位字段与同一容器内的其他位字段比较位范围，不参与字节重叠检查
+        if entry.field.bits.is_some() {
+            continue;
+        }
+        if let Some((last_end, last_index)) = last {
+            let other = &entries[last_index];
+            if start < last_end && end > start && (entry.own || other.own) {
+                diagnostics.error(Some(entry.name.clone()), format!("与字段 {} 重叠", other.name));
+            }
+        }
+        if last.is_none_or(|(last_end, _)| end > last_end) {
+            last = Some((end, index));
+        }
+    }
+
+    for (index, entry) in entries.iter().enumerate() {
+        let Some(ref bits) = entry.field.bits else { continue };
+        let overlapped = entries[..index].iter().find(|other| {
+            (entry.own || other.own)
+                && other.field.offset == entry.field.offset
+                && other.field.field_type.size() == entry.field.field_type.size()
+                && other.field.bits.as_ref().is_some_and(|o| {
+                    o.order == bits.order
+                        && o.offset < bits.offset + bits.width
+                        && bits.offset < o.offset + o.width
+                })
+        });
+        if let Some(other) = overlapped {
+            diagnostics.error(Some(entry.name.clone()), format!("位范围与字段 {} 重叠", other.name));
+        }
+    }
+}
+
+/// 检查判别字段与消息布局
+fn validate_variants(protocol: &Protocol, header_len: usize, diagnostics: &mut Diagnostics) {
+    let has_variants = !protocol.variants.is_empty() || protocol.default_variant.is_some();
+
+    match protocol.discriminator {
+        Some(ref discriminator) => match protocol.fields.iter().find(|f| &f.name == discriminator) {
+            Some(field) if field.field_type.is_integral() => {}
+            Some(_) => diagnostics.error(Some(discriminator.clone()), "判别字段须为整数类型".to_string()),
+            None => diagnostics.error(None, format!("判别字段不存在: {}", discriminator)),
+        },
+        None if has_variants => {
+            diagnostics.warning(None, "未设置判别字段，消息布局不会被选中".to_string());
+        }
+        None => {}
+    }
+
+    for (index, variant) in protocol.variants.iter().enumerate() {
+        if variant.values.is_empty() {
+            diagnostics.warning(None, format!("消息布局 {} 没有判别值，不会被选中", variant.name));
+        }
+        for value in &variant.values {
+            if let Some(earlier) = protocol.variants[..index].iter().find(|v| v.values.contains(value)) {
+                diagnostics.warning(
+                    None,
+                    format!("消息布局 {} 的判别值 0x{:X} 已被 {} 使用，不会被选中", variant.name, value, earlier.name),
+                );
+            }
+        }
+    }
+
+    for variant in protocol.variants.iter().chain(protocol.default_variant.as_ref()) {
+        validate_fields(protocol, &variant.fields, &protocol.fields, Some(&variant.name), header_len, diagnostics);
+    }
+}
+
+/// 检查变长字段之后的字段能否到达
+///
+/// 未设置长度字段、帧尾或成帧编码时流式分帧按最小帧长切分，
+/// 位于变长字段名义范围之后的字段在变长内容超出名义长度时落在帧外。
+fn validate_reachability(protocol: &Protocol, diagnostics: &mut Diagnostics) {
+    let bounded = protocol.length_field.is_some()
+        || protocol.footer.as_ref().is_some_and(|f| !f.is_empty())
+        || protocol.framing.is_some();
+    if bounded {
+        return;
+    }
+    let Some(unbounded) = protocol.fields.iter().find(|f| is_unbounded(protocol, f, 0)) else { return };
+    let end = unbounded.offset + protocol.nominal_size(unbounded);
+
+    let variant_fields = protocol.variants.iter()
+        .chain(protocol.default_variant.as_ref())
+        .flat_map(|v| v.fields.iter().map(move |f| (format!("{}.{}", v.name, f.name), f)));
+    let fields = protocol.fields.iter().map(|f| (f.name.clone(), f)).chain(variant_fields);
+    for (name, field) in fields {
+        if !std::ptr::eq(field, unbounded) && field.offset >= end {
+            diagnostics.warning(
+                Some(name),
+                format!("位于变长字段 {} 之后，未设置长度字段、帧尾或成帧编码时无法分帧到达", unbounded.name),
+            );
+        }
+    }
+}
+
+/// 字段实际长度是否由帧内容决定
+fn is_unbounded(protocol: &Protocol, field: &ProtocolField, depth: usize) -> bool {
+    if field.count_from.is_some() || field.is_terminated() {
+        return true;
+    }
+    if field.field_type == FieldType::Struct {
+        return depth < MAX_STRUCT_DEPTH
+            && field.struct_name.as_deref()
+                .and_then(|name| protocol.find_struct(name))
+                .is_some_and(|s| s.fields.iter().any(|f| is_unbounded(protocol, f, depth + 1)));
+    }
+    field.length_from.is_some() && field.field_type.size().is_none()
+}
+
+/// 检查帧头与最小帧长是否超出单帧上限
+fn validate_frame_length(protocol: &Protocol, header_len: usize, diagnostics: &mut Diagnostics) {
+    if header_len > MAX_FRAME_LENGTH {
+        diagnostics.error(None, format!("帧头长度 {} 超出单帧上限 {}", header_len, MAX_FRAME_LENGTH));
+        return;
+    }
+    let min_length = protocol.min_frame_length();
+    if min_length > MAX_FRAME_LENGTH {
+        diagnostics.error(None, format!("最小帧长 {} 超出单帧上限 {}", min_length, MAX_FRAME_LENGTH));
+    }
+}
+
+/// 检查帧长度字段
+fn validate_length_field(protocol: &Protocol, diagnostics: &mut Diagnostics) {
+    let Some(ref length_field) = protocol.length_field else { return };
+
+    let field = match protocol.fields.iter().find(|f| f.name == length_field.field) {
+        Some(field) if field.field_type.is_integer() => Some(field),
+        Some(_) => {
+            diagnostics.error(Some(length_field.field.clone()), "长度字段须为整数类型".to_string());
+            None
+        }
+        None => {
+            diagnostics.error(None, format!("长度字段不存在: {}", length_field.field));
+            None
+        }
+    };
+    if length_field.multiplier <= 0 {
+        diagnostics.error(Some(length_field.field.clone()), "长度倍数须大于 0".to_string());
+        return;
+    }
+
+    // 长度字段取最大值时仍容纳不下帧头与固定字段，该协议的帧永远无法完整
+    let Some(field) = field else { return };
+    let width = match field.bits {
+        Some(ref bits) => bits.width,
+        None => field.field_type.size().unwrap_or(8) as u32 * 8,
+    };
+    let max_value = if width >= 64 { u64::MAX } else { (1u64 << width) - 1 };
+    let min_length = protocol.min_frame_length();
+    if let Some(max_length) = length_field.frame_length(max_value) {
+        if max_length < min_length {
+            diagnostics.error(
+                Some(length_field.field.clone()),
+                format!("长度字段可表示的最大帧长 {} 小于最小帧长 {}", max_length, min_length),
+            );
+        }
+    }
+}
+
+/// 检查校验布局是否落在帧内
+fn validate_checksum_layout(protocol: &Protocol, header_len: usize, diagnostics: &mut Diagnostics) {
+    let Some(ref layout) = protocol.checksum_layout else { return };
+    let size = protocol.checksum.as_ref().map(|c| c.size()).unwrap_or(0);
+
+    if size == 0 {
+        diagnostics.warning(None, "已设置校验布局但未设置校验类型".to_string());
+        return;
+    }
+    if let Some(end) = layout.end {
+        if layout.start > end {
+            diagnostics.error(None, format!("校验范围起点 {} 大于终点 {}", layout.start, end));
+        }
+    }
+    if let Some(position) = layout.position {
+        if position < header_len {
+            diagnostics.warning(None, format!("校验值位置 {} 位于帧头内", position));
+        }
+        // 校验值不应与字段重叠
+        for field in protocol.fields.iter().filter(|f| f.bits.is_none()) {
+            let start = header_len + field.offset;
+            let end = start + protocol.nominal_size(field);
+            if start < position + size && position < end {
+                diagnostics.error(Some(field.name.clone()), "与校验值位置重叠".to_string());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(protocol: &Protocol) -> Vec<(Severity, Option<String>, String)> {
+        validate_protocol(protocol).into_iter()
+            .map(|d| (d.severity, d.field, d.message))
+            .collect()
+    }
+
+    #[test]
+    fn fields_after_variable_length_field_are_unreachable() {
+        let mut protocol = Protocol::new("变长");
+        protocol.fields = vec![
+            ProtocolField::new("n", FieldType::Uint8, 0),
+            ProtocolField::new("data", FieldType::Bytes, 1)
+                .with_length_from(LengthRef { field: "n".to_string(), adjust: 0, multiplier: 1 }),
+            ProtocolField::new("tail", FieldType::Uint8, 1),
+        ];
+        let diagnostics = messages(&protocol);
+        assert!(diagnostics.iter().any(|(severity, field, message)| {
+            *severity == Severity::Warning && field.as_deref() == Some("tail") && message.contains("data")
+        }));
+
+        protocol.footer = Some(vec![0x0D]);
+        assert!(!messages(&protocol).iter().any(|(_, field, _)| field.as_deref() == Some("tail")));
+    }
+
+    #[test]
+    fn frame_must_fit_length_field_and_limit() {
+        let mut protocol = Protocol::new("长度").with_header(vec![0xAA; 8]);
+        protocol.fields = vec![
+            ProtocolField::new("len", FieldType::Uint8, 0)
+                .with_bits(BitRange { offset: 0, width: 2, order: BitOrder::LsbFirst }),
+        ];
+        protocol.length_field = Some(LengthField {
+            field: "len".to_string(),
+            adjust: 0,
+            multiplier: 1,
+            start: 0,
+            trailing: 0,
+        });
+        assert!(check_protocol(&protocol).unwrap_err().contains("最小帧长 9"));
+
+        let protocol = Protocol::new("帧头").with_header(vec![0xAA; MAX_FRAME_LENGTH + 1]);
+        assert!(check_protocol(&protocol).unwrap_err().contains("帧头长度"));
+    }
+}
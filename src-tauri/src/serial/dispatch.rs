@@ -0,0 +1,132 @@
+use std::sync::atomic::Ordering;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::models::{DataDirection, DataFormat, DataPacket};
+use crate::parser::{ModbusTracker, ModbusTransaction, ParsedFrame, ParserState, ProtocolParser, StreamDecoder};
+use super::ModbusMaster;
+use crate::storage::LogState;
+
+/// 帧解析事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrameEvent {
+    /// 解析结果
+    #[serde(flatten)]
+    pub frame: ParsedFrame,
+    /// 数据方向
+    pub direction: DataDirection,
+    /// 时间戳
+    pub timestamp: i64,
+}
+
+/// 分发一段收发数据：按 `format` 编码后推送到前端并写入日志
+///
+/// 普通会话与嗅探模式共用此入口，保证两者的显示与日志行为一致。
+pub fn dispatch_data<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    bytes: &[u8],
+    direction: DataDirection,
+    format: DataFormat,
+) -> DataPacket {
+    let packet = DataPacket::encode(bytes, direction, format);
+
+    // 发送事件到前端
+    let _ = app_handle.emit("serial:data-received", &packet);
+
+    write_log(app_handle, &packet);
+
+    packet
+}
+
+/// 只写入日志，不推送到前端
+///
+/// 用于前端自行显示的发送数据，使日志同时包含收发两个方向。
+pub fn log_data<R: Runtime>(app_handle: &AppHandle<R>, bytes: &[u8], direction: DataDirection) {
+    write_log(app_handle, &DataPacket::from_bytes(bytes, direction));
+}
+
+/// 写入日志（仅在记录中时生效），连续失败时只通过 `log:error` 事件提示一次
+fn write_log<R: Runtime>(app_handle: &AppHandle<R>, packet: &DataPacket) {
+    let Some(log_state) = app_handle.try_state::<LogState>() else {
+        return;
+    };
+    let Ok(mut manager) = log_state.manager.lock() else {
+        return;
+    };
+
+    match manager.write_data(packet.direction.label(), &packet.data, packet.format.label()) {
+        Ok(()) => log_state.failure_reported.store(false, Ordering::Relaxed),
+        Err(e) => {
+            if !log_state.failure_reported.swap(true, Ordering::Relaxed) {
+                let _ = app_handle.emit("log:error", format!("日志写入失败: {}", e));
+            }
+        }
+    }
+}
+
+/// 将收到的数据送入流式提取器，并推送解析出的帧，返回各帧的原始数据
+///
+/// 内置 Modbus 解码器给出的报文按请求与响应配对，配对成功时另行推送。
+pub fn decode_frames<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    decoder: &mut StreamDecoder,
+    bytes: &[u8],
+    direction: DataDirection,
+) -> Vec<Vec<u8>> {
+    extract_frames(app_handle, direction, |parser, modbus, timestamp| {
+        decoder.push(bytes, parser, modbus, timestamp)
+    })
+}
+
+/// 没有新数据时调用，输出自动识别模式下超过帧间隔仍暂缓的帧，返回各帧的原始数据
+pub fn poll_frames<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    decoder: &mut StreamDecoder,
+    direction: DataDirection,
+) -> Vec<Vec<u8>> {
+    if decoder.is_empty() {
+        return Vec::new();
+    }
+    extract_frames(app_handle, direction, |parser, modbus, timestamp| {
+        decoder.poll(parser, modbus, timestamp)
+    })
+}
+
+/// 在解析器状态上执行一次提取并推送结果
+fn extract_frames<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    direction: DataDirection,
+    extract: impl FnOnce(&ProtocolParser, &mut ModbusTracker, i64) -> Vec<(ParsedFrame, Option<ModbusTransaction>)>,
+) -> Vec<Vec<u8>> {
+    let Some(parser_state) = app_handle.try_state::<ParserState>() else {
+        return Vec::new();
+    };
+
+    let timestamp = chrono::Utc::now().timestamp_millis();
+    let frames = match (parser_state.parser.lock(), parser_state.modbus.lock()) {
+        (Ok(parser), Ok(mut modbus)) => extract(&*parser, &mut *modbus, timestamp),
+        _ => return Vec::new(),
+    };
+
+    let mut raw = Vec::with_capacity(frames.len());
+    for (frame, transaction) in frames {
+        raw.push(frame.raw_data.clone());
+        let _ = app_handle.emit("parser:frame", &FrameEvent {
+            frame,
+            direction: direction.clone(),
+            timestamp,
+        });
+        if let Some(transaction) = transaction {
+            let _ = app_handle.emit("modbus:transaction", &transaction);
+        }
+    }
+    raw
+}
+
+/// 将收到的数据送入 Modbus 主站，用于匹配等待中的响应
+pub fn feed_master<R: Runtime>(app_handle: &AppHandle<R>, bytes: &[u8]) {
+    if let Some(master) = app_handle.try_state::<ModbusMaster>() {
+        master.feed(bytes);
+    }
+}
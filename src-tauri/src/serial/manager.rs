@@ -1,4 +1,4 @@
-use std::io::{Read, Write};
+use std::io::{self, ErrorKind, Read, Write};
 use std::sync::Arc;
 use std::thread::{self, JoinHandle};
 use std::time::Duration;
@@ -7,12 +7,23 @@ use serialport::{SerialPort, SerialPortType};
 use tauri::{AppHandle, Emitter, Runtime};
 
 use crate::models::*;
-use super::DataThrottler;
+use crate::parser::StreamDecoder;
+use super::{decode_frames, dispatch_data, feed_master, open_transport, poll_frames, DataThrottler, LineSplitter, Transport};
+
+/// WebSocket 会话断开后的重连间隔
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(2);
 
 /// 串口管理器
+///
+/// 各字段共享于读取任务，克隆得到的实例操作同一会话。
+#[derive(Clone)]
 pub struct SerialManager {
     /// 当前打开的串口
-    port: Arc<Mutex<Option<Box<dyn SerialPort>>>>,
+    port: Arc<Mutex<Option<Box<dyn Transport>>>>,
+    /// 会话期间需保持打开的附属句柄（如 PTY 从端）
+    guard: Arc<Mutex<Option<Box<dyn SerialPort>>>>,
+    /// 虚拟串口路径
+    virtual_path: Arc<Mutex<Option<String>>>,
     /// 当前配置
     config: Arc<Mutex<Option<SerialConfig>>>,
     /// 接收任务句柄
@@ -26,6 +37,8 @@ impl SerialManager {
     pub fn new() -> Self {
         Self {
             port: Arc::new(Mutex::new(None)),
+            guard: Arc::new(Mutex::new(None)),
+            virtual_path: Arc::new(Mutex::new(None)),
             config: Arc::new(Mutex::new(None)),
             read_task: Arc::new(Mutex::new(None)),
             running: Arc::new(Mutex::new(false)),
@@ -40,80 +53,106 @@ impl SerialManager {
         let port_infos: Vec<PortInfo> = ports
             .into_iter()
             .map(|p| {
-                let (port_type, manufacturer, product) = match &p.port_type {
-                    SerialPortType::UsbPort(info) => {
-                        let manufacturer = info.manufacturer.clone();
-                        let product = info.product.clone();
-                        ("USB".to_string(), manufacturer, product)
+                let mut info = PortInfo {
+                    name: p.port_name,
+                    port_type: String::new(),
+                    manufacturer: None,
+                    product: None,
+                    vid: None,
+                    pid: None,
+                    serial_number: None,
+                };
+
+                match p.port_type {
+                    SerialPortType::UsbPort(usb) => {
+                        info.port_type = "USB".to_string();
+                        info.manufacturer = usb.manufacturer;
+                        info.product = usb.product;
+                        info.vid = Some(usb.vid);
+                        info.pid = Some(usb.pid);
+                        info.serial_number = usb.serial_number;
                     }
                     SerialPortType::BluetoothPort => {
-                        ("Bluetooth".to_string(), None, None)
+                        info.port_type = "Bluetooth".to_string();
                     }
                     SerialPortType::PciPort => {
-                        ("PCI".to_string(), None, None)
+                        info.port_type = "PCI".to_string();
                     }
                     SerialPortType::Unknown => {
-                        ("Unknown".to_string(), None, None)
+                        info.port_type = "Unknown".to_string();
                     }
-                };
-
-                PortInfo {
-                    name: p.port_name,
-                    port_type,
-                    manufacturer,
-                    product,
                 }
+
+                info
             })
             .collect();
 
         Ok(port_infos)
     }
 
+    /// 按选择条件查找端口名称
+    pub fn resolve_port(selector: &PortSelector) -> Result<String, String> {
+        if let PortSelector::Name { name } = selector {
+            return Ok(name.clone());
+        }
+
+        Self::list_ports()?
+            .into_iter()
+            .find(|p| selector.matches(p))
+            .map(|p| p.name)
+            .ok_or_else(|| "未找到符合条件的串口".to_string())
+    }
+
     /// 打开串口
     pub fn open(&self, config: SerialConfig) -> Result<(), String> {
+        if self.port.lock().is_some() {
+            return Err("串口已打开，请先关闭".to_string());
+        }
+
+        // 断线后仍在重连的会话由新会话取代
+        self.stop_read_task();
+
         let mut port_guard = self.port.lock();
-        
         if port_guard.is_some() {
             return Err("串口已打开，请先关闭".to_string());
         }
 
-        let port = serialport::new(&config.port_name, config.baud_rate)
-            .data_bits(config.data_bits.clone().into())
-            .stop_bits(config.stop_bits.clone().into())
-            .parity(config.parity.clone().into())
-            .flow_control(config.flow_control.clone().into())
-            .timeout(Duration::from_millis(100))
-            .open()
-            .map_err(|e| format!("无法打开串口 {}: {}", config.port_name, e))?;
+        let opened = open_transport(&config)?;
 
-        *port_guard = Some(port);
+        *port_guard = Some(opened.transport);
+        *self.guard.lock() = opened.guard;
+        *self.virtual_path.lock() = opened.virtual_path;
         *self.config.lock() = Some(config);
 
         Ok(())
     }
 
-    /// 关闭串口
+    /// 关闭串口，断线后正在重连的会话同样停止
     pub fn close(&self) -> Result<(), String> {
-        // 设置停止标志
-        *self.running.lock() = false;
-
-        // 等待读取线程结束
-        if let Some(handle) = self.read_task.lock().take() {
-            let _ = handle.join();
-        }
+        self.stop_read_task();
 
         // 关闭串口
         let mut port_guard = self.port.lock();
-        if port_guard.is_none() {
+        if port_guard.is_none() && self.config.lock().is_none() {
             return Err("串口未打开".to_string());
         }
 
         *port_guard = None;
+        *self.guard.lock() = None;
+        *self.virtual_path.lock() = None;
         *self.config.lock() = None;
 
         Ok(())
     }
 
+    /// 设置停止标志并等待读取线程结束
+    fn stop_read_task(&self) {
+        *self.running.lock() = false;
+        if let Some(handle) = self.read_task.lock().take() {
+            let _ = handle.join();
+        }
+    }
+
     /// 发送数据
     pub fn send(&self, data: &[u8]) -> Result<(), String> {
         let mut port_guard = self.port.lock();
@@ -141,59 +180,83 @@ impl SerialManager {
         }
     }
 
-    /// 启动读取任务
-    pub fn start_read_task<R: Runtime>(&self, app_handle: AppHandle<R>) {
+    /// 获取当前会话配置
+    pub fn config(&self) -> Option<SerialConfig> {
+        self.config.lock().clone()
+    }
+
+    /// 获取虚拟串口路径（仅 PTY 会话）
+    pub fn virtual_path(&self) -> Option<String> {
+        self.virtual_path.lock().clone()
+    }
+
+    /// 启动读取任务，按 `mode` 分包并编码后推送到前端
+    ///
+    /// 连接断开时释放端口并通知前端会话已关闭；WebSocket 会话随后按原配置定期重连。
+    pub fn start_read_task<R: Runtime>(&self, app_handle: AppHandle<R>, mode: ReceiveMode) {
         // 设置运行标志
         *self.running.lock() = true;
         
-        let port = Arc::clone(&self.port);
-        let running = Arc::clone(&self.running);
+        let manager = self.clone();
 
         let handle = thread::spawn(move || {
             let mut throttler = DataThrottler::new(50);
+            let mut lines = LineSplitter::default();
+            let mut decoder = StreamDecoder::new();
             let mut buffer = [0u8; 4096];
 
             loop {
                 // 检查是否应该停止
-                if !*running.lock() {
+                if !*manager.running.lock() {
                     break;
                 }
 
-                let mut port_guard = port.lock();
-                if let Some(ref mut port) = *port_guard {
-                    // 尝试读取数据
-                    match port.read(&mut buffer) {
-                        Ok(n) if n > 0 => {
-                            let data = &buffer[..n];
-                            
-                            // 使用节流器处理数据
-                            if let Some(throttled_data) = throttler.push(data) {
-                                // 转换为十六进制字符串
-                                let hex_string = throttled_data
-                                    .iter()
-                                    .map(|b| format!("{:02X}", b))
-                                    .collect::<Vec<_>>()
-                                    .join(" ");
-
-                                let packet = DataPacket {
-                                    id: uuid::Uuid::new_v4().to_string(),
-                                    data: hex_string,
-                                    timestamp: chrono::Utc::now().timestamp_millis(),
-                                    direction: DataDirection::Rx,
-                                    format: DataFormat::Hex,
-                                };
-
-                                // 发送事件到前端
-                                let _ = app_handle.emit("serial:data-received", &packet);
-                            }
+                let mut port_guard = manager.port.lock();
+                let Some(ref mut port) = *port_guard else {
+                    // 串口已关闭，退出任务
+                    break;
+                };
+
+                // 尝试读取数据
+                match port.read(&mut buffer) {
+                    Ok(n) if n > 0 => {
+                        let data = &buffer[..n];
+                        
+                        // 按激活协议实时分帧
+                        let frames = decode_frames(&app_handle, &mut decoder, data, DataDirection::Rx);
+                        feed_master(&app_handle, data);
+                        
+                        // 原始模式使用节流器处理数据，协议模式下未能分帧的数据不显示
+                        let packets = match mode.framing {
+                            FramingMode::Raw => throttler.push(data).into_iter().collect(),
+                            FramingMode::Line => lines.push(data),
+                            FramingMode::Protocol => frames,
+                        };
+                        for packet in packets {
+                            dispatch_data(&app_handle, &packet, DataDirection::Rx, mode.encoding.clone());
+                        }
+                    }
+                    Err(e) if is_disconnect(&e) => {
+                        *port_guard = None;
+                        drop(port_guard);
+                        if !manager.reconnect(&app_handle, &e) {
+                            break;
                         }
-                        _ => {
-                            // 超时或无数据，继续
+                        // 重连后从新数据开始分帧
+                        throttler.clear();
+                        lines = LineSplitter::default();
+                        decoder.reset();
+                        continue;
+                    }
+                    _ => {
+                        // 超时或无数据：输出超过帧间隔仍暂缓的帧
+                        let frames = poll_frames(&app_handle, &mut decoder, DataDirection::Rx);
+                        if mode.framing == FramingMode::Protocol {
+                            for frame in frames {
+                                dispatch_data(&app_handle, &frame, DataDirection::Rx, mode.encoding.clone());
+                            }
                         }
                     }
-                } else {
-                    // 串口已关闭，退出任务
-                    break;
                 }
                 
                 // 释放锁后短暂休眠
@@ -204,6 +267,64 @@ impl SerialManager {
 
         *self.read_task.lock() = Some(handle);
     }
+
+    /// 处理连接断开（端口已释放）：通知前端会话关闭，WebSocket 会话按原配置定期重连
+    ///
+    /// 重连成功返回 `true`；非 WebSocket 会话或会话被关闭、取代时返回 `false`。
+    fn reconnect<R: Runtime>(&self, app_handle: &AppHandle<R>, error: &io::Error) -> bool {
+        *self.guard.lock() = None;
+        *self.virtual_path.lock() = None;
+        let message = format!("连接已断开: {}", error);
+        let _ = app_handle.emit("serial:error", serde_json::json!({ "message": message }));
+        let _ = app_handle.emit("serial:status-changed", &SerialStatus::Closed);
+
+        let config = self.config.lock().clone();
+        let config = match config {
+            Some(config) if config.transport == TransportType::WebSocket => config,
+            _ => {
+                *self.config.lock() = None;
+                *self.running.lock() = false;
+                return false;
+            }
+        };
+
+        while self.wait_running(RECONNECT_INTERVAL) {
+            let Ok(opened) = open_transport(&config) else { continue };
+
+            let mut port = self.port.lock();
+            if !*self.running.lock() {
+                return false;
+            }
+            *port = Some(opened.transport);
+            *self.guard.lock() = opened.guard;
+            *self.virtual_path.lock() = opened.virtual_path;
+            let _ = app_handle.emit("serial:status-changed", &SerialStatus::Open);
+            return true;
+        }
+        false
+    }
+
+    /// 等待一段时间，期间会话停止时提前返回 `false`
+    fn wait_running(&self, duration: Duration) -> bool {
+        let step = Duration::from_millis(100);
+        let mut waited = Duration::ZERO;
+        while waited < duration {
+            if !*self.running.lock() {
+                return false;
+            }
+            thread::sleep(step);
+            waited += step;
+        }
+        *self.running.lock()
+    }
+}
+
+/// 读取错误是否表示连接已断开
+fn is_disconnect(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        ErrorKind::ConnectionAborted | ErrorKind::ConnectionReset | ErrorKind::NotConnected | ErrorKind::BrokenPipe
+    )
 }
 
 impl Default for SerialManager {
@@ -212,21 +333,48 @@ impl Default for SerialManager {
     }
 }
 
-/// 解析十六进制字符串为字节数组
-pub fn parse_hex_string(hex: &str) -> Result<Vec<u8>, String> {
-    let clean_hex: String = hex.chars()
-        .filter(|c| !c.is_whitespace())
-        .collect();
+/// 按配置打开物理串口
+pub fn open_serial_port(config: &SerialConfig) -> Result<Box<dyn SerialPort>, String> {
+    serialport::new(&config.port_name, config.baud_rate)
+        .data_bits(config.data_bits.clone().into())
+        .stop_bits(config.stop_bits.clone().into())
+        .parity(config.parity.clone().into())
+        .flow_control(config.flow_control.clone().into())
+        .timeout(Duration::from_millis(100))
+        .open()
+        .map_err(|e| format!("无法打开串口 {}: {}", config.port_name, e))
+}
 
-    if clean_hex.len() % 2 != 0 {
-        return Err("十六进制字符串长度必须为偶数".to_string());
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use tauri::Listener;
+
+    #[test]
+    fn loopback_session_reads_back_sent_data() {
+        let app = tauri::test::mock_app();
+        let (sender, receiver) = mpsc::channel();
+        app.listen_any("serial:data-received", move |event| {
+            let packet: DataPacket = serde_json::from_str(event.payload()).unwrap();
+            let _ = sender.send(packet);
+        });
 
-    (0..clean_hex.len())
-        .step_by(2)
-        .map(|i| {
-            u8::from_str_radix(&clean_hex[i..i + 2], 16)
-                .map_err(|e| format!("无效的十六进制字符: {}", e))
-        })
-        .collect()
+        let manager = SerialManager::new();
+        manager.open(SerialConfig {
+            transport: TransportType::Loopback,
+            ..SerialConfig::default()
+        }).unwrap();
+        manager.start_read_task(app.handle().clone(), ReceiveMode::default());
+        manager.send(&[0x01, 0x02, 0xAB]).unwrap();
+
+        let packet = receiver.recv_timeout(Duration::from_secs(2)).unwrap();
+        assert_eq!(packet.direction, DataDirection::Rx);
+        assert_eq!(packet.data, "01 02 AB");
+        assert!(packet.timestamp_us / 1000 == packet.timestamp);
+
+        manager.close().unwrap();
+        assert_eq!(manager.status(), SerialStatus::Closed);
+    }
 }
+
@@ -0,0 +1,110 @@
+use std::time::{Duration, Instant};
+use parking_lot::{Condvar, Mutex};
+
+use crate::parser::{decode_adu, find_response, ModbusKind, ModbusMessage, ModbusMode, ModbusRequest, ModbusTransaction};
+
+/// Modbus 主站
+///
+/// 发出请求后在接收数据中查找对应的响应，与当前激活的协议无关。
+pub struct ModbusMaster {
+    /// 等待响应的请求
+    pending: Mutex<Option<PendingRequest>>,
+    /// 响应到达通知
+    arrived: Condvar,
+    /// 同一时间只允许一个请求等待响应
+    busy: Mutex<()>,
+}
+
+/// 等待响应的请求
+struct PendingRequest {
+    /// 传输模式
+    mode: ModbusMode,
+    /// 请求报文
+    request: ModbusMessage,
+    /// 发出请求后收到的数据
+    buffer: Vec<u8>,
+    /// 匹配到的响应
+    response: Option<ModbusMessage>,
+}
+
+impl ModbusMaster {
+    /// 创建新的主站
+    pub fn new() -> Self {
+        Self {
+            pending: Mutex::new(None),
+            arrived: Condvar::new(),
+            busy: Mutex::new(()),
+        }
+    }
+
+    /// 发送请求并等待响应，广播请求发出后直接返回 `None`
+    ///
+    /// `send` 负责把编码后的 ADU 写出。
+    pub fn transact(
+        &self,
+        mode: ModbusMode,
+        request: &ModbusRequest,
+        timeout: Duration,
+        send: impl FnOnce(&[u8]) -> Result<(), String>,
+    ) -> Result<Option<ModbusTransaction>, String> {
+        let _busy = self.busy.lock();
+
+        let adu = request.encode(mode)?;
+        let (message, _) = decode_adu(&adu, mode, Some(ModbusKind::Request))?;
+        if request.slave == 0 {
+            send(&adu)?;
+            return Ok(None);
+        }
+
+        *self.pending.lock() = Some(PendingRequest {
+            mode,
+            request: message.clone(),
+            buffer: Vec::new(),
+            response: None,
+        });
+
+        let sent_at = Instant::now();
+        if let Err(e) = send(&adu) {
+            *self.pending.lock() = None;
+            return Err(e);
+        }
+
+        let deadline = sent_at + timeout;
+        let mut pending = self.pending.lock();
+        let mut timed_out = false;
+        loop {
+            if let Some(response) = pending.as_mut().and_then(|p| p.response.take()) {
+                *pending = None;
+                let elapsed = sent_at.elapsed().as_millis() as i64;
+                return Ok(Some(ModbusTransaction::new(message, response, elapsed)));
+            }
+            if timed_out {
+                break;
+            }
+            timed_out = self.arrived.wait_until(&mut pending, deadline).timed_out();
+        }
+
+        *pending = None;
+        Err(format!("从站 {} 无响应（超时 {} ms）", request.slave, timeout.as_millis()))
+    }
+
+    /// 送入接收数据，匹配到等待中请求的响应时唤醒请求方
+    pub fn feed(&self, bytes: &[u8]) {
+        let mut pending = self.pending.lock();
+        let Some(request) = pending.as_mut().filter(|p| p.response.is_none()) else {
+            return;
+        };
+
+        request.buffer.extend_from_slice(bytes);
+        if let Some(response) = find_response(&request.buffer, request.mode, &request.request) {
+            request.response = Some(response);
+            self.arrived.notify_all();
+        }
+    }
+}
+
+impl Default for ModbusMaster {
+    fn default() -> Self {
+        Self::new()
+    }
+}
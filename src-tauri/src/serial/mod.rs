@@ -1,5 +1,15 @@
 mod manager;
 mod throttler;
+mod dispatch;
+mod sniffer;
+mod transport;
+mod watcher;
+mod master;
 
 pub use manager::*;
 pub use throttler::*;
+pub use dispatch::*;
+pub use sniffer::*;
+pub use transport::*;
+pub use watcher::*;
+pub use master::*;
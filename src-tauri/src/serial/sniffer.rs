@@ -0,0 +1,191 @@
+use std::io::{ErrorKind, Read, Write};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use parking_lot::Mutex;
+use serialport::SerialPort;
+use tauri::{AppHandle, Emitter, Runtime};
+
+use crate::models::*;
+use crate::parser::StreamDecoder;
+use super::{decode_frames, dispatch_data, open_serial_port, poll_frames};
+
+/// 双串口嗅探管理器
+///
+/// 同时打开 A、B 两个串口，将 A 口收到的数据转发到 B 口、B 口收到的数据转发到 A 口，
+/// 并把两个方向的数据按时间顺序推送到前端。
+pub struct SnifferManager {
+    /// 当前配置
+    config: Arc<Mutex<Option<SnifferConfig>>>,
+    /// 转发任务句柄
+    tasks: Arc<Mutex<Vec<JoinHandle<()>>>>,
+    /// 运行标志
+    running: Arc<Mutex<bool>>,
+    /// A→B 已转发字节数
+    bytes_a_to_b: Arc<Mutex<u64>>,
+    /// B→A 已转发字节数
+    bytes_b_to_a: Arc<Mutex<u64>>,
+}
+
+impl SnifferManager {
+    /// 创建新的嗅探管理器
+    pub fn new() -> Self {
+        Self {
+            config: Arc::new(Mutex::new(None)),
+            tasks: Arc::new(Mutex::new(Vec::new())),
+            running: Arc::new(Mutex::new(false)),
+            bytes_a_to_b: Arc::new(Mutex::new(0)),
+            bytes_b_to_a: Arc::new(Mutex::new(0)),
+        }
+    }
+
+    /// 启动嗅探
+    pub fn start<R: Runtime>(&self, config: SnifferConfig, app_handle: AppHandle<R>) -> Result<(), String> {
+        if *self.running.lock() {
+            return Err("嗅探模式已在运行，请先停止".to_string());
+        }
+
+        if config.port_a.port_name == config.port_b.port_name {
+            return Err("A 口与 B 口不能是同一个串口".to_string());
+        }
+
+        // 回收因读取错误而自行退出的转发线程
+        for handle in self.tasks.lock().drain(..) {
+            let _ = handle.join();
+        }
+
+        let port_a = open_serial_port(&config.port_a)?;
+        let port_b = open_serial_port(&config.port_b)?;
+
+        // 每个方向各持有一份读句柄和一份写句柄
+        let port_a_writer = port_a.try_clone()
+            .map_err(|e| format!("无法复制串口句柄 {}: {}", config.port_a.port_name, e))?;
+        let port_b_writer = port_b.try_clone()
+            .map_err(|e| format!("无法复制串口句柄 {}: {}", config.port_b.port_name, e))?;
+
+        *self.running.lock() = true;
+        *self.bytes_a_to_b.lock() = 0;
+        *self.bytes_b_to_a.lock() = 0;
+
+        let mut tasks = self.tasks.lock();
+        tasks.push(self.spawn_forwarder(
+            port_a,
+            port_b_writer,
+            DataDirection::AToB,
+            Arc::clone(&self.bytes_a_to_b),
+            app_handle.clone(),
+        ));
+        tasks.push(self.spawn_forwarder(
+            port_b,
+            port_a_writer,
+            DataDirection::BToA,
+            Arc::clone(&self.bytes_b_to_a),
+            app_handle,
+        ));
+
+        *self.config.lock() = Some(config);
+
+        Ok(())
+    }
+
+    /// 停止嗅探
+    ///
+    /// 转发线程因读取失败自行停止后，仍会清理残留状态并正常返回。
+    pub fn stop(&self) -> Result<(), String> {
+        if !*self.running.lock() && self.config.lock().is_none() {
+            return Err("嗅探模式未运行".to_string());
+        }
+
+        // 设置停止标志
+        *self.running.lock() = false;
+
+        // 等待转发线程结束，线程退出时会释放串口
+        for handle in self.tasks.lock().drain(..) {
+            let _ = handle.join();
+        }
+
+        *self.config.lock() = None;
+
+        Ok(())
+    }
+
+    /// 获取状态
+    pub fn status(&self) -> SnifferStatus {
+        let config = self.config.lock();
+        SnifferStatus {
+            running: *self.running.lock(),
+            port_a: config.as_ref().map(|c| c.port_a.port_name.clone()),
+            port_b: config.as_ref().map(|c| c.port_b.port_name.clone()),
+            bytes_a_to_b: *self.bytes_a_to_b.lock(),
+            bytes_b_to_a: *self.bytes_b_to_a.lock(),
+        }
+    }
+
+    /// 启动单向转发任务
+    fn spawn_forwarder<R: Runtime>(
+        &self,
+        mut source: Box<dyn SerialPort>,
+        mut target: Box<dyn SerialPort>,
+        direction: DataDirection,
+        counter: Arc<Mutex<u64>>,
+        app_handle: AppHandle<R>,
+    ) -> JoinHandle<()> {
+        let running = Arc::clone(&self.running);
+
+        thread::spawn(move || {
+            let mut decoder = StreamDecoder::new();
+            let mut buffer = [0u8; 4096];
+
+            loop {
+                // 检查是否应该停止
+                if !*running.lock() {
+                    break;
+                }
+
+                match source.read(&mut buffer) {
+                    Ok(n) if n > 0 => {
+                        let data = &buffer[..n];
+
+                        // 先转发再上报，尽量减少对被监听链路的时延影响
+                        if let Err(e) = target.write_all(data).and_then(|_| target.flush()) {
+                            let _ = app_handle.emit(
+                                "sniffer:error",
+                                format!("{} 转发失败: {}", direction.label(), e),
+                            );
+                        }
+
+                        *counter.lock() += n as u64;
+
+                        // 嗅探模式不做节流，每次读取单独成包以保留精确时间戳
+                        dispatch_data(&app_handle, data, direction.clone(), DataFormat::Hex);
+                        decode_frames(&app_handle, &mut decoder, data, direction.clone());
+                    }
+                    Ok(_) => {
+                        poll_frames(&app_handle, &mut decoder, direction.clone());
+                        thread::sleep(Duration::from_millis(10));
+                    }
+                    Err(e) if e.kind() == ErrorKind::TimedOut => {
+                        // 超时无数据，输出超过帧间隔仍暂缓的帧后短暂休眠
+                        poll_frames(&app_handle, &mut decoder, direction.clone());
+                        thread::sleep(Duration::from_millis(10));
+                    }
+                    Err(e) => {
+                        // 串口断开等不可恢复的错误：停止两个方向的转发并通知前端
+                        let _ = app_handle.emit(
+                            "sniffer:error",
+                            format!("{} 读取失败，嗅探已停止: {}", direction.label(), e),
+                        );
+                        *running.lock() = false;
+                        break;
+                    }
+                }
+            }
+        })
+    }
+}
+
+impl Default for SnifferManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -65,3 +65,42 @@ impl Default for DataThrottler {
         Self::new(50)
     }
 }
+
+/// 单行最大长度，超过后不等换行直接输出
+const MAX_LINE_LENGTH: usize = 4096;
+
+/// 按换行分包
+/// 缓冲数据直到遇到 `\n`，每行（含换行符）作为一包
+#[derive(Default)]
+pub struct LineSplitter {
+    buffer: Vec<u8>,
+}
+
+impl LineSplitter {
+    /// 推送数据，返回其中完整的行
+    pub fn push(&mut self, data: &[u8]) -> Vec<Vec<u8>> {
+        self.buffer.extend_from_slice(data);
+
+        let mut lines = Vec::new();
+        while let Some(pos) = self.buffer.iter().position(|b| *b == b'\n') {
+            lines.push(self.buffer.drain(..=pos).collect());
+        }
+        if self.buffer.len() >= MAX_LINE_LENGTH {
+            lines.push(std::mem::take(&mut self.buffer));
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_splitter_waits_for_newline() {
+        let mut lines = LineSplitter::default();
+        assert!(lines.push(b"$GPGGA,1").is_empty());
+        assert_eq!(lines.push(b"23\r\nOK\r\n$"), [b"$GPGGA,123\r\n".to_vec(), b"OK\r\n".to_vec()]);
+        assert_eq!(lines.push(&[b'x'; MAX_LINE_LENGTH]).concat().len(), MAX_LINE_LENGTH + 1);
+    }
+}
@@ -0,0 +1,212 @@
+use std::collections::VecDeque;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::sync::Arc;
+use std::time::Duration;
+use parking_lot::{Condvar, Mutex};
+use serialport::SerialPort;
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket};
+
+use crate::models::*;
+use super::open_serial_port;
+
+/// 会话传输层
+///
+/// 串口会话只依赖读写能力，物理串口、回环和伪终端都通过该 trait 接入 `SerialManager`。
+pub trait Transport: Read + Write + Send {}
+
+impl<T: Read + Write + Send + ?Sized> Transport for T {}
+
+/// 已打开的传输层
+pub struct OpenedTransport {
+    /// 读写句柄
+    pub transport: Box<dyn Transport>,
+    /// 需要在会话期间保持打开的附属句柄（如 PTY 从端）
+    pub guard: Option<Box<dyn SerialPort>>,
+    /// 供外部程序连接的虚拟串口路径
+    pub virtual_path: Option<String>,
+}
+
+/// 按配置打开传输层
+pub fn open_transport(config: &SerialConfig) -> Result<OpenedTransport, String> {
+    match config.transport {
+        TransportType::Serial => Ok(OpenedTransport {
+            transport: Box::new(open_serial_port(config)?),
+            guard: None,
+            virtual_path: None,
+        }),
+        TransportType::Loopback => Ok(OpenedTransport {
+            transport: Box::new(LoopbackPort::new()),
+            guard: None,
+            virtual_path: None,
+        }),
+        TransportType::Pty => open_pty(),
+        TransportType::WebSocket => Ok(OpenedTransport {
+            transport: Box::new(WebSocketPort::connect(&config.port_name)?),
+            guard: None,
+            virtual_path: None,
+        }),
+    }
+}
+
+/// 打开伪终端对，本端持有主端，从端路径供外部程序连接
+#[cfg(unix)]
+fn open_pty() -> Result<OpenedTransport, String> {
+    let (master, slave) = serialport::TTYPort::pair()
+        .map_err(|e| format!("无法创建虚拟串口对: {}", e))?;
+
+    let slave_path = slave.name()
+        .ok_or("无法获取虚拟串口路径")?;
+
+    Ok(OpenedTransport {
+        transport: Box::new(master),
+        guard: Some(Box::new(slave)),
+        virtual_path: Some(slave_path),
+    })
+}
+
+#[cfg(not(unix))]
+fn open_pty() -> Result<OpenedTransport, String> {
+    Err("当前平台不支持虚拟串口对".to_string())
+}
+
+/// 进程内回环端口
+///
+/// 写入的数据进入内部缓冲区，随后由读取端原样读出，用于无硬件时调试命令组和协议。
+pub struct LoopbackPort {
+    /// 回环缓冲区
+    buffer: Arc<(Mutex<VecDeque<u8>>, Condvar)>,
+    /// 读取超时
+    timeout: Duration,
+}
+
+impl LoopbackPort {
+    /// 创建新的回环端口
+    pub fn new() -> Self {
+        Self {
+            buffer: Arc::new((Mutex::new(VecDeque::new()), Condvar::new())),
+            timeout: Duration::from_millis(100),
+        }
+    }
+}
+
+impl Default for LoopbackPort {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Read for LoopbackPort {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let (lock, condvar) = &*self.buffer;
+        let mut queue = lock.lock();
+
+        if queue.is_empty() {
+            condvar.wait_for(&mut queue, self.timeout);
+        }
+
+        if queue.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "Operation timed out"));
+        }
+
+        let n = buf.len().min(queue.len());
+        for (dst, src) in buf.iter_mut().zip(queue.drain(..n)) {
+            *dst = src;
+        }
+
+        Ok(n)
+    }
+}
+
+impl Write for LoopbackPort {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let (lock, condvar) = &*self.buffer;
+        lock.lock().extend(buf);
+        condvar.notify_all();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// WebSocket 客户端端口
+///
+/// 二进制帧和文本帧的内容都作为接收数据，发送的数据封装为二进制帧。
+pub struct WebSocketPort {
+    /// WebSocket 连接
+    socket: WebSocket<MaybeTlsStream<TcpStream>>,
+    /// 已收到但尚未读出的数据
+    pending: VecDeque<u8>,
+}
+
+impl WebSocketPort {
+    /// 连接到 WebSocket 服务端
+    pub fn connect(url: &str) -> Result<Self, String> {
+        if !url.starts_with("ws://") {
+            return Err(format!("不支持的 WebSocket 地址 {}，仅支持 ws://", url));
+        }
+
+        let (socket, _) = tungstenite::connect(url)
+            .map_err(|e| format!("无法连接 {}: {}", url, e))?;
+
+        // 设置读取超时，与串口保持一致，避免读取线程长期占用锁
+        if let MaybeTlsStream::Plain(stream) = socket.get_ref() {
+            stream.set_read_timeout(Some(Duration::from_millis(100)))
+                .map_err(|e| format!("设置读取超时失败: {}", e))?;
+        }
+
+        Ok(Self {
+            socket,
+            pending: VecDeque::new(),
+        })
+    }
+}
+
+impl Read for WebSocketPort {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pending.is_empty() {
+            match self.socket.read() {
+                Ok(Message::Binary(data)) => self.pending.extend(data),
+                Ok(Message::Text(text)) => self.pending.extend(text.into_bytes()),
+                Ok(Message::Close(_)) => {
+                    return Err(io::Error::new(io::ErrorKind::ConnectionAborted, "WebSocket closed"));
+                }
+                Ok(_) => {
+                    // Ping 的应答已由 tungstenite 排队，这里立即发出
+                    let _ = self.socket.flush();
+                }
+                Err(tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed) => {
+                    return Err(io::Error::new(io::ErrorKind::ConnectionAborted, "WebSocket closed"));
+                }
+                Err(tungstenite::Error::Io(e)) => return Err(e),
+                Err(e) => return Err(io::Error::other(e)),
+            }
+        }
+
+        if self.pending.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "Operation timed out"));
+        }
+
+        let n = buf.len().min(self.pending.len());
+        for (dst, src) in buf.iter_mut().zip(self.pending.drain(..n)) {
+            *dst = src;
+        }
+
+        Ok(n)
+    }
+}
+
+impl Write for WebSocketPort {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.socket.send(Message::Binary(buf.to_vec()))
+            .map_err(io::Error::other)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.socket.flush().map_err(io::Error::other)
+    }
+}
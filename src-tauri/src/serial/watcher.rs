@@ -0,0 +1,44 @@
+use std::collections::HashSet;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::models::*;
+use super::SerialManager;
+
+/// 启动串口监视任务
+///
+/// 定期枚举串口，发现新增或移除时调用 `on_change`。
+/// 首次枚举时已存在的串口视为新出现，便于启动时统一处理。
+pub fn spawn_port_watcher<F>(interval_ms: u64, mut on_change: F) -> JoinHandle<()>
+where
+    F: FnMut(&PortChange) + Send + 'static,
+{
+    thread::spawn(move || {
+        let mut known: HashSet<String> = HashSet::new();
+
+        loop {
+            if let Ok(ports) = SerialManager::list_ports() {
+                let current: HashSet<String> = ports.iter().map(|p| p.name.clone()).collect();
+
+                let arrived: Vec<PortInfo> = ports
+                    .iter()
+                    .filter(|p| !known.contains(&p.name))
+                    .cloned()
+                    .collect();
+                let removed: Vec<String> = known.difference(&current).cloned().collect();
+
+                if !arrived.is_empty() || !removed.is_empty() {
+                    on_change(&PortChange {
+                        ports,
+                        arrived,
+                        removed,
+                    });
+                }
+
+                known = current;
+            }
+
+            thread::sleep(Duration::from_millis(interval_ms));
+        }
+    })
+}
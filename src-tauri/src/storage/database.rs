@@ -5,6 +5,16 @@ use tauri::Manager;
 use tauri::AppHandle;
 use tauri::Runtime;
 use rusqlite::OptionalExtension;
+use crate::parser::Protocol;
+
+/// 当前数据库结构版本，记录在 `PRAGMA user_version`
+const SCHEMA_VERSION: i32 = 1;
+
+/// 激活协议在设置表中的键
+const ACTIVE_PROTOCOL_KEY: &str = "active_protocol";
+
+/// 协议自动识别模式在设置表中的键
+const AUTO_DETECT_KEY: &str = "protocol_auto_detect";
 
 /// 数据库配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -79,6 +89,7 @@ impl DatabaseManager {
 
         // 创建表
         self.create_tables(&conn)?;
+        self.migrate(&conn)?;
 
         self.initialized = true;
         Ok(())
@@ -130,6 +141,33 @@ impl DatabaseManager {
         Ok(())
     }
 
+    /// 按 `user_version` 逐级升级数据库结构
+    fn migrate(&self, conn: &rusqlite::Connection) -> Result<(), String> {
+        let version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+            .map_err(|e| format!("Failed to read schema version: {}", e))?;
+
+        if version < 1 {
+            // 协议表，完整定义以 JSON 保存
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS protocols (
+                    id TEXT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    definition TEXT NOT NULL,
+                    created_at INTEGER NOT NULL,
+                    updated_at INTEGER NOT NULL
+                )",
+                [],
+            ).map_err(|e| format!("Failed to create protocols table: {}", e))?;
+        }
+
+        if version < SCHEMA_VERSION {
+            conn.execute_batch(&format!("PRAGMA user_version = {}", SCHEMA_VERSION))
+                .map_err(|e| format!("Failed to update schema version: {}", e))?;
+        }
+
+        Ok(())
+    }
+
     /// 获取数据库连接
     pub fn get_connection(&self) -> Result<rusqlite::Connection, String> {
         rusqlite::Connection::open(&self.path)
@@ -180,6 +218,104 @@ impl DatabaseManager {
         Ok(settings)
     }
 
+    /// 删除设置
+    pub fn delete_setting(&self, key: &str) -> Result<(), String> {
+        let conn = self.get_connection()?;
+
+        conn.execute("DELETE FROM settings WHERE key = ?1", [key])
+            .map_err(|e| format!("Failed to delete setting: {}", e))?;
+
+        Ok(())
+    }
+
+    /// 保存协议，已存在时仅接受 `updated_at` 不早于库中版本的定义
+    ///
+    /// 库中已有更新的版本时不写入，返回 `false`。
+    pub fn save_protocol(&self, protocol: &Protocol) -> Result<bool, String> {
+        let conn = self.get_connection()?;
+        let definition = serde_json::to_string(protocol)
+            .map_err(|e| format!("Failed to serialize protocol: {}", e))?;
+
+        let changed = conn.execute(
+            "INSERT INTO protocols (id, name, definition, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name,
+                definition = excluded.definition,
+                updated_at = excluded.updated_at
+             WHERE excluded.updated_at >= protocols.updated_at",
+            rusqlite::params![
+                protocol.id,
+                protocol.name,
+                definition,
+                protocol.created_at,
+                protocol.updated_at,
+            ],
+        ).map_err(|e| format!("Failed to save protocol: {}", e))?;
+
+        Ok(changed > 0)
+    }
+
+    /// 加载所有协议，跳过无法解析的记录
+    pub fn load_protocols(&self) -> Result<Vec<Protocol>, String> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare("SELECT id, definition FROM protocols ORDER BY created_at")
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(|e| format!("Failed to query protocols: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to collect protocols: {}", e))?;
+
+        let protocols = rows.into_iter()
+            .filter_map(|(id, definition)| match serde_json::from_str::<Protocol>(&definition) {
+                Ok(protocol) => Some(protocol),
+                Err(e) => {
+                    eprintln!("Warning: Failed to load protocol {}: {}", id, e);
+                    None
+                }
+            })
+            .collect();
+
+        Ok(protocols)
+    }
+
+    /// 删除协议
+    pub fn delete_protocol(&self, id: &str) -> Result<(), String> {
+        let conn = self.get_connection()?;
+
+        conn.execute("DELETE FROM protocols WHERE id = ?1", [id])
+            .map_err(|e| format!("Failed to delete protocol: {}", e))?;
+
+        Ok(())
+    }
+
+    /// 获取激活的协议 ID
+    pub fn get_active_protocol(&self) -> Result<Option<String>, String> {
+        self.get_setting(ACTIVE_PROTOCOL_KEY)
+    }
+
+    /// 保存激活的协议 ID
+    pub fn save_active_protocol(&self, id: Option<&str>) -> Result<(), String> {
+        match id {
+            Some(id) => self.save_setting(ACTIVE_PROTOCOL_KEY, id),
+            None => self.delete_setting(ACTIVE_PROTOCOL_KEY),
+        }
+    }
+
+    /// 获取是否启用协议自动识别
+    pub fn get_auto_detect(&self) -> Result<bool, String> {
+        Ok(self.get_setting(AUTO_DETECT_KEY)?.as_deref() == Some("true"))
+    }
+
+    /// 保存是否启用协议自动识别
+    pub fn save_auto_detect(&self, enabled: bool) -> Result<(), String> {
+        self.save_setting(AUTO_DETECT_KEY, if enabled { "true" } else { "false" })
+    }
+
     /// 检查是否已初始化
     pub fn is_initialized(&self) -> bool {
         self.initialized
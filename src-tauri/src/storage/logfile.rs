@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use std::fs::{File, OpenOptions};
 use std::io::{BufWriter, Write};
 use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
 use std::sync::Mutex;
 
 /// 日志状态
@@ -313,12 +314,15 @@ impl LogManager {
 /// 应用状态中的日志管理器
 pub struct LogState {
     pub manager: Mutex<LogManager>,
+    /// 写入失败是否已提示，写入恢复后重置
+    pub failure_reported: AtomicBool,
 }
 
 impl LogState {
     pub fn new(manager: LogManager) -> Self {
         Self {
             manager: Mutex::new(manager),
+            failure_reported: AtomicBool::new(false),
         }
     }
 }
@@ -8,6 +8,13 @@ interface ParserPanelProps {
   editingProtocol: Protocol | null;
 }
 
+// 协议设置已生效但未写入数据库时给出警告
+function warnPersist(warning: string | null) {
+  if (warning) {
+    console.warn(warning);
+  }
+}
+
 export function ParserPanel({ onEditProtocol, editingProtocol }: ParserPanelProps) {
   const [protocols, setProtocols] = useState<Protocol[]>([]);
   const [activeProtocolId, setActiveProtocolId] = useState<string | null>(null);
@@ -40,11 +47,11 @@ export function ParserPanel({ onEditProtocol, editingProtocol }: ParserPanelProp
 
   const handleDeleteProtocol = async (id: string) => {
     try {
-      await invoke('delete_protocol', { id });
+      warnPersist(await invoke<string | null>('delete_protocol', { id }));
       setProtocols(protocols.filter((p) => p.id !== id));
       if (activeProtocolId === id) {
         setActiveProtocolId(null);
-        await invoke('set_active_protocol', { id: null });
+        warnPersist(await invoke<string | null>('set_active_protocol', { id: null }));
       }
     } catch (error) {
       console.error('Failed to delete protocol:', error);
@@ -54,10 +61,10 @@ export function ParserPanel({ onEditProtocol, editingProtocol }: ParserPanelProp
   const handleSetActive = async (id: string) => {
     try {
       if (activeProtocolId === id) {
-        await invoke('set_active_protocol', { id: null });
+        warnPersist(await invoke<string | null>('set_active_protocol', { id: null }));
         setActiveProtocolId(null);
       } else {
-        await invoke('set_active_protocol', { id });
+        warnPersist(await invoke<string | null>('set_active_protocol', { id }));
         setActiveProtocolId(id);
       }
     } catch (error) {
@@ -67,7 +74,7 @@ export function ParserPanel({ onEditProtocol, editingProtocol }: ParserPanelProp
 
   const handleSaveProtocol = async (protocol: Protocol) => {
     try {
-      await invoke('save_protocol', { protocol });
+      warnPersist(await invoke<string | null>('save_protocol', { protocol }));
       await loadProtocols();
       onEditProtocol(null);
     } catch (error) {
@@ -155,12 +155,17 @@ export function ProtocolEditor({ protocol, onSave, onCancel }: ProtocolEditorPro
               <div>
                 <label className="text-xs text-muted-foreground">校验方式</label>
                 <select
-                  value={editingProtocol.checksum || 'none'}
-                  onChange={(e) =>
+                  value={
+                    typeof editingProtocol.checksum === 'object'
+                      ? 'custom'
+                      : editingProtocol.checksum || 'none'
+                  }
+                  onChange={(e) => {
+                    if (e.target.value === 'custom') return;
                     updateProtocol({
                       checksum: e.target.value === 'none' ? undefined : (e.target.value as any),
-                    })
-                  }
+                    });
+                  }}
                   className="w-full mt-1 px-3 py-2 rounded-lg border bg-background focus:outline-none focus:ring-2 focus:ring-primary/50"
                 >
                   <option value="none">无校验</option>
@@ -170,6 +175,11 @@ export function ProtocolEditor({ protocol, onSave, onCancel }: ProtocolEditorPro
                   <option value="crc8">CRC8</option>
                   <option value="crc16">CRC16</option>
                   <option value="crc32">CRC32</option>
+                  {typeof editingProtocol.checksum === 'object' && (
+                    <option value="custom">
+                      {editingProtocol.checksum.crc.name ?? 'CRC（自定义）'}
+                    </option>
+                  )}
                 </select>
               </div>
             </div>
@@ -4,7 +4,7 @@ import { Trash2, Download, FileText, FileSpreadsheet, FileJson, CheckCircle, Ale
 import { invoke } from '@tauri-apps/api/core';
 import { save } from '@tauri-apps/plugin-dialog';
 import { useSerialStore } from '../../stores';
-import { formatTimestamp, cn } from '../../utils';
+import { formatPacketTimestamp, directionLabel, cn } from '../../utils';
 
 type ExportFormat = 'txt' | 'csv' | 'json';
 
@@ -49,8 +49,8 @@ export function DataDisplay() {
   const formatDataAsText = () => {
     return receivedData
       .map((packet) => {
-        const timestamp = formatTimestamp(packet.timestamp);
-        const direction = packet.direction === 'tx' ? 'TX' : 'RX';
+        const timestamp = formatPacketTimestamp(packet);
+        const direction = directionLabel(packet.direction);
         return `[${timestamp}] ${direction} ${packet.data}`;
       })
       .join('\n');
@@ -192,7 +192,7 @@ export function DataDisplay() {
                     )}
                   >
                     <span className="text-muted-foreground shrink-0">
-                      [{formatTimestamp(packet.timestamp)}]
+                      [{formatPacketTimestamp(packet)}]
                     </span>
                     <span
                       className={cn(
@@ -202,7 +202,7 @@ export function DataDisplay() {
                           : 'bg-green-500/20 text-green-500'
                       )}
                     >
-                      {packet.direction === 'tx' ? 'TX' : 'RX'}
+                      {directionLabel(packet.direction)}
                     </span>
                     <span className="break-all">{packet.data}</span>
                   </div>
@@ -102,9 +102,9 @@ export const initSerialEventListeners = async () => {
     });
   });
 
-  // 监听状态变化
-  await listen<{ status: SerialStatus }>('serial:status-changed', (event) => {
-    useSerialStore.setState({ status: event.payload.status });
+  // 监听状态变化（串口拔出、WebSocket 断开及重连）
+  await listen<SerialStatus>('serial:status-changed', (event) => {
+    useSerialStore.setState({ status: event.payload });
   });
 
   // 监听错误
@@ -3,3 +3,4 @@ export * from './command';
 export * from './settings';
 export type { LogStatus, LogConfig } from './settings';
 export * from './parser';
+export * from './profile';
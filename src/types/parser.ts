@@ -1,5 +1,7 @@
 // 协议解析器相关类型定义
 
+import type { DataDirection } from './serial';
+
 export type FieldType =
   | 'uint8'
   | 'uint16'
@@ -9,14 +11,34 @@ export type FieldType =
   | 'int16'
   | 'int32'
   | 'int64'
+  | 'float16'
   | 'float32'
   | 'float64'
   | 'string'
+  | 'cstring'
   | 'bytes'
-  | 'hex';
+  | 'hex'
+  | 'bcd'
+  | 'asciihex'
+  | 'bool'
+  | 'timestamp'
+  | 'timestampms'
+  | 'struct';
 
 export type ByteOrder = 'bigEndian' | 'littleEndian';
 
+// CRC 参数（Rocksoft 模型），数值以 "0x..." 十六进制字符串表示以保留 64 位精度
+export interface CrcSpec {
+  name?: string;
+  width: number;
+  poly: string;
+  init: string;
+  refin: boolean;
+  refout: boolean;
+  xorout: string;
+  check?: string;
+}
+
 export type ChecksumType =
   | 'none'
   | 'sum8'
@@ -24,18 +46,123 @@ export type ChecksumType =
   | 'crc8'
   | 'crc16'
   | 'crc32'
-  | 'xor8';
+  | 'xor8'
+  | 'lrc8'
+  | { crc: CrcSpec };
+
+// 成帧编码：HDLC 参数缺省为 flag 0x7E、escape 0x7D、xor 0x20
+export interface HdlcParams {
+  flag?: number;
+  escape?: number;
+  xor?: number;
+}
+
+export type Framing = 'slip' | 'cobs' | { hdlc: HdlcParams };
+
+// 文本匹配方式：正则的命名捕获组对应同名字段；键值对缺省以 ";" 分隔、以 "=" 连接
+export type TextGrammar =
+  | { regex: { pattern: string } }
+  | { keyValue: { pairSeparator?: string; keySeparator?: string } };
+
+// 内置解码器：设置后由解码器给出字段，字段定义与帧格式设置不起作用
+export type ModbusMode = 'rtu' | 'ascii';
+
+export type BuiltinDecoder = { modbus: { mode: ModbusMode } } | 'nmea';
+
+export interface TextFormat {
+  grammar: TextGrammar;
+  // 行结束符，缺省为 "\n"
+  lineEnding?: string;
+}
+
+// 校验布局：偏移相对帧起始（含帧头），缺省时从第 0 字节算到校验值之前，校验值紧邻帧尾之前
+export interface ChecksumLayout {
+  start: number;
+  end?: number;
+  position?: number;
+  byteOrder: ByteOrder;
+}
+
+export interface ChecksumResult {
+  checksumType: ChecksumType;
+  expected: string;
+  actual: string;
+  valid: boolean;
+}
+
+// 字段长度引用：长度 = 引用字段值 × multiplier + adjust
+export interface LengthRef {
+  field: string;
+  adjust?: number;
+  multiplier?: number;
+}
+
+// 帧长度字段：帧总长 = start + 字段值 × multiplier + adjust + trailing
+export interface LengthField {
+  field: string;
+  adjust?: number;
+  multiplier?: number;
+  start?: number;
+  trailing?: number;
+}
+
+// 位字段：在整数容器内按位取值，位宽为 1 时作为布尔标志
+export type BitOrder = 'lsbFirst' | 'msbFirst';
+
+export interface BitRange {
+  offset: number;
+  width: number;
+  order?: BitOrder;
+}
+
+// 取值映射，value 可为数字或 "0x.." 字符串
+export interface ValueLabel {
+  value: string | number;
+  label: string;
+}
+
+export interface ValueMap {
+  entries: ValueLabel[];
+  fallback?: string;
+}
 
 export interface ProtocolField {
   name: string;
   fieldType: FieldType;
   offset: number;
   length?: number;
+  lengthFrom?: LengthRef;
+  bits?: BitRange;
+  // 数组：固定个数或由其他字段决定个数
+  count?: number;
+  countFrom?: LengthRef;
+  // 子结构名称（fieldType 为 struct 时）
+  structName?: string;
+  valueMap?: ValueMap;
+  // 工程值 = 原始值 × scale + valueOffset
+  scale?: number;
+  valueOffset?: number;
+  unit?: string;
+  precision?: number;
   byteOrder: ByteOrder;
   description?: string;
   visible: boolean;
 }
 
+// 可复用的子结构，字段偏移相对结构起始
+export interface ProtocolStruct {
+  name: string;
+  description?: string;
+  fields: ProtocolField[];
+}
+
+// 消息布局：判别字段取值命中 values 时使用，values 可为数字或 "0x.." 字符串
+export interface ProtocolVariant {
+  name: string;
+  values: (string | number)[];
+  fields: ProtocolField[];
+}
+
 export interface Protocol {
   id: string;
   name: string;
@@ -43,17 +170,64 @@ export interface Protocol {
   header?: number[];
   footer?: number[];
   fields: ProtocolField[];
+  structs?: ProtocolStruct[];
+  discriminator?: string;
+  variants?: ProtocolVariant[];
+  defaultVariant?: ProtocolVariant;
   checksum?: ChecksumType;
+  checksumLayout?: ChecksumLayout;
+  lengthField?: LengthField;
+  // 设置后按定界字节分帧，帧头、字段与校验均基于解除转义后的数据
+  framing?: Framing;
+  // 设置后按行匹配文本，二进制布局、校验与成帧编码不起作用
+  text?: TextFormat;
+  decoder?: BuiltinDecoder;
   createdAt: number;
   updatedAt: number;
 }
 
+export type FieldErrorKind =
+  | 'insufficientData'
+  | 'invalidLength'
+  | 'unresolvedReference'
+  | 'invalidBitRange'
+  | 'unsupportedType'
+  | 'nestingTooDeep'
+  | 'invalidEncoding';
+
+export interface FieldError {
+  kind: FieldErrorKind;
+  message: string;
+}
+
+// 类型化字段值
+// 64 位整数（unsigned、signed、timestamp 与 enum 的 value）在 ±(2^53 - 1) 以内为数字，
+// 超出时为十进制字符串，可用 BigInt() 无损转换
+export type SafeInteger = number | string;
+
+export type FieldValue =
+  | { type: 'unsigned'; value: SafeInteger }
+  | { type: 'signed'; value: SafeInteger }
+  | { type: 'float'; value: number }
+  | { type: 'bool'; value: boolean }
+  | { type: 'string'; value: string }
+  // Unix 时间戳（毫秒）
+  | { type: 'timestamp'; value: SafeInteger }
+  | { type: 'bytes'; value: number[] }
+  | { type: 'enum'; value: { value: SafeInteger; label: string } }
+  | { type: 'error'; value: FieldError };
+
 export interface ParsedField {
   name: string;
   fieldType: FieldType;
   rawBytes: number[];
-  value: string;
+  value: FieldValue;
+  display?: string;
+  rawValue?: number;
+  scaledValue?: number;
+  unit?: string;
   description?: string;
+  children?: ParsedField[];
 }
 
 export interface ParsedFrame {
@@ -62,8 +236,22 @@ export interface ParsedFrame {
   fields: ParsedField[];
   valid: boolean;
   error?: string;
+  checksum?: ChecksumResult;
+  variant?: string;
+  // 解除转义后的帧数据（配置了成帧编码时）
+  unstuffed?: number[];
+  // 自动识别时得分相同或接近的其他协议，按得分从高到低
+  ambiguous?: string[];
+  // 内置 Modbus 解码器给出的报文
+  modbus?: ModbusMessage;
 }
 
+// 流式分帧事件（parser:frame）
+export type FrameEvent = ParsedFrame & {
+  direction: DataDirection;
+  timestamp: number;
+};
+
 // 字段类型选项
 export const FIELD_TYPE_OPTIONS: { value: FieldType; label: string; size?: number }[] = [
   { value: 'uint8', label: 'UINT8', size: 1 },
@@ -74,11 +262,18 @@ export const FIELD_TYPE_OPTIONS: { value: FieldType; label: string; size?: numbe
   { value: 'int32', label: 'INT32', size: 4 },
   { value: 'uint64', label: 'UINT64', size: 8 },
   { value: 'int64', label: 'INT64', size: 8 },
+  { value: 'float16', label: 'FLOAT16', size: 2 },
   { value: 'float32', label: 'FLOAT32', size: 4 },
   { value: 'float64', label: 'FLOAT64', size: 8 },
+  { value: 'bool', label: 'BOOL', size: 1 },
+  { value: 'timestamp', label: 'TIMESTAMP (s)', size: 4 },
+  { value: 'timestampms', label: 'TIMESTAMP (ms)', size: 8 },
   { value: 'string', label: 'STRING' },
+  { value: 'cstring', label: 'CSTRING' },
   { value: 'bytes', label: 'BYTES' },
   { value: 'hex', label: 'HEX' },
+  { value: 'bcd', label: 'BCD' },
+  { value: 'asciihex', label: 'ASCII HEX' },
 ];
 
 // 创建空协议
@@ -101,3 +296,67 @@ export const createEmptyField = (): ProtocolField => ({
   description: '',
   visible: true,
 });
+
+// 协议导入冲突处理方式
+export type ImportConflict = 'overwrite' | 'skip' | 'keepBoth';
+
+export interface ImportReport {
+  added: string[];
+  replaced: string[];
+  skipped: string[];
+  // 已导入但未能保存到数据库的协议及原因
+  warnings: string[];
+  // 因库中已有更新的版本而未导入的协议及原因
+  failed: string[];
+}
+
+// 协议校验诊断
+export type Severity = 'error' | 'warning';
+
+export interface Diagnostic {
+  severity: Severity;
+  field?: string;
+  message: string;
+}
+
+// 帧编码：字段名 → 取值。整数与浮点数为数值（也可为 "0x.." 字符串或取值映射名称），
+// 标志位为布尔，字符串字段为文本，字节字段为十六进制文本或字节数组，子结构为对象，数组为列表
+export type FrameValues = Record<string, unknown>;
+
+// 编码结果，hex 与命令数据格式相同
+export interface EncodedFrame {
+  data: number[];
+  hex: string;
+}
+
+// Modbus 报文，values 为线圈状态（0/1）或寄存器值，异常响应的 function 不含 0x80
+export type ModbusKind = 'request' | 'response' | 'exception';
+
+export interface ModbusMessage {
+  slave: number;
+  function: number;
+  kind: ModbusKind;
+  address?: number;
+  quantity?: number;
+  writeAddress?: number;
+  writeQuantity?: number;
+  values?: number[];
+  exception?: number;
+}
+
+// 配对的请求与响应（modbus:transaction 事件及主站请求结果），elapsed 单位为毫秒
+export interface ModbusTransaction {
+  request: ModbusMessage;
+  response: ModbusMessage;
+  elapsed: number;
+}
+
+// 主站请求：功能码 1–6、15、16、23；slave 为 0 时广播（仅写操作）
+export interface ModbusRequest {
+  slave: number;
+  function: number;
+  address: number;
+  quantity?: number;
+  writeAddress?: number;
+  values?: number[];
+}
@@ -0,0 +1,42 @@
+// 连接配置档案相关类型定义
+
+import { SerialConfig } from './serial';
+
+// 串口选择方式：按名称或按 USB VID/PID/序列号
+export type PortSelector =
+  | { type: 'name'; name: string }
+  | { type: 'usb'; vid: number; pid: number; serialNumber?: string };
+
+// 接收数据分包方式：按时间节流、按换行、按协议分帧（仅显示解析出的帧）
+export type FramingMode = 'raw' | 'line' | 'protocol';
+
+export interface ConnectionProfile {
+  name: string;
+  port: PortSelector;
+  config: SerialConfig;
+  // 接收数据的显示编码
+  encoding: 'hex' | 'ascii';
+  framing: FramingMode;
+  activeProtocol?: string;
+  commandGroup?: string;
+  createdAt: number;
+  updatedAt: number;
+}
+
+// 按连接配置打开会话的结果，warning 为激活协议未能保存到数据库时的警告
+export interface OpenedProfile {
+  profile: ConnectionProfile;
+  warning?: string;
+}
+
+// 自动连接规则：匹配的设备出现时以指定档案（须为物理串口档案）打开，可选开始记录日志并发送命令组
+export interface AutoConnectRule {
+  id: string;
+  name: string;
+  enabled: boolean;
+  selector: PortSelector;
+  profile: string;
+  startLogging: boolean;
+  commandGroup?: string;
+  intervalMs: number;
+}
@@ -7,6 +7,7 @@ export interface SerialConfig {
   stopBits: StopBits;
   parity: Parity;
   flowControl: FlowControl;
+  transport?: TransportType;
 }
 
 // 后端使用 lowercase 枚举，所以前端需要发送字符串
@@ -18,6 +19,9 @@ export type Parity = 'none' | 'odd' | 'even';
 
 export type FlowControl = 'none' | 'software' | 'hardware';
 
+// 传输类型：物理串口、进程内回环、伪终端对（仅 Unix）、WebSocket 客户端
+export type TransportType = 'serial' | 'loopback' | 'pty' | 'websocket';
+
 export type SerialStatus = 'closed' | 'open' | 'error';
 
 export interface PortInfo {
@@ -25,16 +29,36 @@ export interface PortInfo {
   portType: string;
   manufacturer?: string;
   product?: string;
+  vid?: number;
+  pid?: number;
+  serialNumber?: string;
 }
 
+export type DataDirection = 'rx' | 'tx' | 'a2b' | 'b2a';
+
 export interface DataPacket {
   id: string;
   data: string;
   timestamp: number;
-  direction: 'rx' | 'tx';
+  timestampUs?: number;
+  direction: DataDirection;
   format: 'hex' | 'ascii';
 }
 
+// 双串口嗅探配置
+export interface SnifferConfig {
+  portA: SerialConfig;
+  portB: SerialConfig;
+}
+
+export interface SnifferStatus {
+  running: boolean;
+  portA?: string;
+  portB?: string;
+  bytesAToB: number;
+  bytesBToA: number;
+}
+
 // 默认串口配置
 export const DEFAULT_SERIAL_CONFIG: SerialConfig = {
   portName: '',
@@ -43,6 +67,7 @@ export const DEFAULT_SERIAL_CONFIG: SerialConfig = {
   stopBits: 'one',
   parity: 'none',
   flowControl: 'none',
+  transport: 'serial',
 };
 
 // 常用波特率
@@ -1,5 +1,6 @@
 import { clsx, type ClassValue } from 'clsx';
 import { twMerge } from 'tailwind-merge';
+import type { DataDirection, DataPacket } from '../types';
 
 /**
  * 合并 Tailwind CSS 类名
@@ -55,6 +56,33 @@ export function formatTimestamp(timestamp: number): string {
   return `${hours}:${minutes}:${seconds}.${ms}`;
 }
 
+/**
+ * 格式化数据包时间戳，有微秒级时间戳时精确到微秒
+ */
+export function formatPacketTimestamp(packet: DataPacket): string {
+  if (!packet.timestampUs) {
+    return formatTimestamp(packet.timestamp);
+  }
+  const micros = (packet.timestampUs % 1000).toString().padStart(3, '0');
+  return `${formatTimestamp(Math.floor(packet.timestampUs / 1000))}${micros}`;
+}
+
+/**
+ * 获取数据方向的显示标签
+ */
+export function directionLabel(direction: DataDirection): string {
+  switch (direction) {
+    case 'tx':
+      return 'TX';
+    case 'a2b':
+      return 'A→B';
+    case 'b2a':
+      return 'B→A';
+    default:
+      return 'RX';
+  }
+}
+
 /**
  * 验证十六进制字符串
  */